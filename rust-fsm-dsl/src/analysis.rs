@@ -0,0 +1,208 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::*;
+
+use crate::parser::{Source, TransitionDef};
+
+/// A transition between the actual states of the machine after expanding the
+/// transitions of superstates and `_` transitions.
+pub struct Edge {
+    pub from: Ident,
+    /// `None` for transitions that keep the current state.
+    pub to: Option<Ident>,
+    pub input: Ident,
+    /// Whether the transition may not be taken because of the patterns or the
+    /// guards of the state or the input.
+    pub refutable: bool,
+}
+
+/// Deduplicates the identifiers keeping the first occurrence, so the
+/// diagnostics point at the place where an item is mentioned first.
+fn first_occurrences<'a>(idents: impl IntoIterator<Item = &'a Ident>) -> Vec<&'a Ident> {
+    let mut unique: Vec<&Ident> = vec![];
+    for ident in idents {
+        if !unique.contains(&ident) {
+            unique.push(ident);
+        }
+    }
+    unique
+}
+
+/// Finds the problems in the definition of the machine:
+///
+/// * states that cannot be reached from the initial state;
+/// * states without outgoing transitions that are not marked as final;
+/// * inputs that cannot be consumed in any of the reachable states.
+///
+/// The checks requiring an initial state are skipped if it is not declared.
+/// Guards are not evaluated, so a guarded transition is considered possible.
+pub fn analyze<'a>(
+    states: impl IntoIterator<Item = &'a Ident>,
+    inputs: impl IntoIterator<Item = &'a Ident>,
+    initial_state: Option<&Ident>,
+    final_states: &[Ident],
+    edges: &[Edge],
+) -> Vec<Error> {
+    let states = first_occurrences(states);
+    let inputs = first_occurrences(inputs);
+    let mut errors = vec![];
+
+    let reachable = initial_state.map(|initial_state| {
+        let mut reachable = vec![initial_state];
+        let mut i = 0;
+        while let Some(&state) = reachable.get(i) {
+            for edge in edges.iter().filter(|edge| &edge.from == state) {
+                if let Some(to) = edge.to.as_ref().filter(|to| !reachable.contains(to)) {
+                    reachable.push(to);
+                }
+            }
+            i += 1;
+        }
+        reachable
+    });
+
+    for state in &states {
+        if reachable
+            .as_ref()
+            .is_some_and(|reachable| !reachable.contains(state))
+        {
+            errors.push(Error::new_spanned(
+                state,
+                format!("rust-fsm: state `{state}` is unreachable from the initial state"),
+            ));
+        } else if !final_states.contains(state) && !edges.iter().any(|edge| &edge.from == *state) {
+            errors.push(Error::new_spanned(
+                state,
+                format!(
+                    "rust-fsm: state `{state}` has no outgoing transitions and is not marked as final"
+                ),
+            ));
+        }
+    }
+
+    if let Some(reachable) = &reachable {
+        for input in inputs {
+            if !edges
+                .iter()
+                .any(|edge| &edge.input == input && reachable.contains(&&edge.from))
+            {
+                errors.push(Error::new_spanned(
+                    input,
+                    format!("rust-fsm: input `{input}` cannot be consumed in any reachable state"),
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Finds the combinations of a state and an input that are not handled by any
+/// transition that is always taken. Final states do not need to handle any
+/// inputs.
+pub fn find_unhandled<'a>(
+    states: impl IntoIterator<Item = &'a Ident>,
+    inputs: impl IntoIterator<Item = &'a Ident>,
+    final_states: &[Ident],
+    edges: &[Edge],
+) -> Vec<Error> {
+    let inputs = first_occurrences(inputs);
+    first_occurrences(states)
+        .into_iter()
+        .filter(|state| !final_states.contains(state))
+        .filter_map(|state| {
+            let unhandled = inputs
+                .iter()
+                .filter(|input| {
+                    !edges.iter().any(|edge| {
+                        &edge.from == state && &&edge.input == *input && !edge.refutable
+                    })
+                })
+                .map(|input| format!("`{input}`"))
+                .collect::<Vec<_>>();
+            (!unhandled.is_empty()).then(|| {
+                Error::new_spanned(
+                    state,
+                    format!(
+                        "rust-fsm: state `{state}` has no transition on {}",
+                        unhandled.join(", ")
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Turns a diagnostic into a warning. There is no stable API for emitting
+/// warnings from procedural macros, so this relies on using a deprecated item
+/// at the span of the diagnostic.
+pub fn to_warning(error: &Error) -> TokenStream {
+    let message = error.to_string();
+    quote_spanned! {error.span()=>
+        const _: () = {
+            #[deprecated(note = #message)]
+            #[allow(non_upper_case_globals)]
+            const warning: () = ();
+            warning
+        };
+    }
+}
+
+/// Finds the transitions declared more than once for the same state and input.
+/// Returns the errors for the exact duplicates, and the diagnostics for the
+/// guarded transitions that are shadowed by an earlier unguarded one.
+pub fn find_duplicates(transitions: &[TransitionDef]) -> (Vec<Error>, Vec<Error>) {
+    let mut duplicates = vec![];
+    let mut shadowed = vec![];
+    // The patterns of the state and the input with the location of the
+    // transitions not having any guards. Patterns matching any data are
+    // compared by the name of the variant.
+    let mut unguarded: Vec<(String, String, &Ident)> = vec![];
+    for def in transitions {
+        let (source, source_guard) = match &def.source {
+            Source::State(initial_state) => (
+                initial_state.normalized_pattern(),
+                initial_state.separate().1,
+            ),
+            Source::Any(except) => (quote!(_ except #(#except)|*).to_string(), None),
+        };
+        for transition in &def.transitions {
+            let input = transition.input_value.normalized_pattern();
+            let guard = transition.input_value.separate().1;
+            let span = &transition.input_value.ident;
+            let Some((.., previous)) = unguarded
+                .iter()
+                .find(|(x, y, _)| x == &source && y == &input)
+            else {
+                if source_guard.is_none() && guard.is_none() {
+                    unguarded.push((source.clone(), input, span));
+                }
+                continue;
+            };
+            let source = match &def.source {
+                Source::State(initial_state) => format!("`{}`", initial_state.ident),
+                Source::Any(_) => "any state".to_string(),
+            };
+            if source_guard.is_none() && guard.is_none() {
+                let mut error = Error::new_spanned(
+                    span,
+                    format!("rust-fsm: duplicate transition from {source} on `{span}`"),
+                );
+                error.combine(Error::new_spanned(
+                    previous,
+                    "rust-fsm: the transition is first defined here",
+                ));
+                duplicates.push(error);
+            } else {
+                shadowed.push(Error::new_spanned(
+                    span,
+                    format!(
+                        "rust-fsm: the guarded transition from {source} on `{span}` is \
+                         shadowed by the unguarded one defined earlier"
+                    ),
+                ));
+            }
+        }
+    }
+    (duplicates, shadowed)
+}
@@ -0,0 +1,112 @@
+use std::fmt::Write;
+
+use syn::Ident;
+
+use crate::graph::Graph;
+
+/// Quotes a string as a DOT identifier, joining the lines with line breaks.
+fn quote<T: AsRef<str>>(lines: impl IntoIterator<Item = T>) -> String {
+    let lines = lines
+        .into_iter()
+        .map(|x| x.as_ref().replace('\\', "\\\\").replace('"', "\\\""))
+        .collect::<Vec<_>>();
+    format!("\"{}\"", lines.join("\\n"))
+}
+
+/// The node the initial state is pointed at from. It is not an identifier, so
+/// it cannot collide with a state.
+const START: &str = "\"[*]\"";
+
+/// The identifier of the cluster drawing a superstate.
+fn cluster(state: &Ident) -> String {
+    quote([format!("cluster_{state}")])
+}
+
+impl Graph<'_> {
+    /// The name of the state followed by its entry and exit actions.
+    fn label(&self, state: &Ident) -> Vec<String> {
+        let enter = self.enter_actions_of(state).into_iter();
+        let exit = self.exit_actions_of(state).into_iter();
+        std::iter::once(state.to_string())
+            .chain(enter.map(|x| format!("enter / {x}")))
+            .chain(exit.map(|x| format!("exit / {x}")))
+            .collect()
+    }
+
+    fn write_state(&self, dot: &mut String, state: &Ident, indent: &str) {
+        if self.hierarchy.is_superstate(state) {
+            writeln!(dot, "{indent}subgraph {} {{", cluster(state)).unwrap();
+            writeln!(dot, "{indent}    label={};", quote(self.label(state))).unwrap();
+            for child in self.hierarchy.children(state) {
+                self.write_state(dot, child, &format!("{indent}    "));
+            }
+            writeln!(dot, "{indent}}}").unwrap();
+        } else {
+            let peripheries = if self.final_states.contains(state) {
+                ", peripheries=2"
+            } else {
+                ""
+            };
+            writeln!(
+                dot,
+                "{indent}{} [label={}{peripheries}];",
+                quote([state.to_string()]),
+                quote(self.label(state))
+            )
+            .unwrap();
+        }
+    }
+
+    /// Renders the diagram in the DOT language of Graphviz.
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("digraph {} {{\n", quote([self.name.to_string()]));
+        dot.push_str("    compound=true;\n");
+        dot.push_str("    node [shape=box, style=rounded];\n");
+        if let Some(initial_state) = self.initial_state {
+            writeln!(dot, "    {START} [shape=point];").unwrap();
+            writeln!(
+                dot,
+                "    {START} -> {};",
+                quote([initial_state.to_string()])
+            )
+            .unwrap();
+        }
+        for state in self.top_level_states() {
+            self.write_state(&mut dot, state, "    ");
+        }
+        for edge in self.edges {
+            // Edges cannot start or end at a cluster, so they are connected to
+            // a state inside it and clipped at the border.
+            let mut attributes = vec![];
+            let from = if self.hierarchy.is_superstate(&edge.from) {
+                attributes.push(format!("ltail={}", cluster(&edge.from)));
+                self.hierarchy.initial_leaf(&edge.from)
+            } else {
+                &edge.from
+            };
+            let to = edge.to.as_ref().unwrap_or(&edge.from);
+            let to = if self.hierarchy.is_superstate(to) {
+                attributes.push(format!("lhead={}", cluster(to)));
+                self.hierarchy.initial_leaf(to)
+            } else {
+                to
+            };
+            let label = if edge.outputs.is_empty() {
+                edge.label.clone()
+            } else {
+                format!("{} / {}", edge.label, edge.outputs.join(", "))
+            };
+            attributes.insert(0, format!("label={}", quote([label])));
+            writeln!(
+                dot,
+                "    {} -> {} [{}];",
+                quote([from.to_string()]),
+                quote([to.to_string()]),
+                attributes.join(", ")
+            )
+            .unwrap();
+        }
+        dot.push('}');
+        dot
+    }
+}
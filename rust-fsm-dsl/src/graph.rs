@@ -0,0 +1,65 @@
+// Each of the formats only uses a part of the model.
+#![cfg_attr(
+    not(all(feature = "dot", feature = "plantuml", feature = "scxml")),
+    allow(dead_code)
+)]
+
+use syn::Ident;
+
+use crate::{hierarchy::Hierarchy, variant::Final};
+
+/// A transition as drawn in the diagrams. The transitions of superstates are
+/// drawn once, and `_` transitions are drawn from every state they apply to.
+pub struct Edge {
+    pub from: Ident,
+    /// `None` for the transitions that keep the current state.
+    pub to: Option<Ident>,
+    /// The input as written in the definition, including its guard.
+    pub label: String,
+    /// The name of the input.
+    pub input: Ident,
+    /// The input without its guard.
+    pub pattern: String,
+    /// The guards of the state and the input.
+    pub guard: Option<String>,
+    pub outputs: Vec<String>,
+}
+
+/// The definition of a machine as shown in the diagrams and the other
+/// exported formats.
+pub struct Graph<'a> {
+    pub name: &'a Ident,
+    pub states: &'a [Ident],
+    pub initial_state: Option<&'a Ident>,
+    pub final_states: &'a [Ident],
+    pub hierarchy: &'a Hierarchy,
+    pub enter_actions: &'a [(Ident, Final)],
+    pub exit_actions: &'a [(Ident, Final)],
+    pub edges: &'a [Edge],
+    /// The types of the data carried by the states.
+    pub payloads: &'a [(Ident, String)],
+}
+
+impl Graph<'_> {
+    /// The names of the outputs produced when entering the state.
+    pub fn enter_actions_of(&self, state: &Ident) -> Vec<String> {
+        crate::actions_of(self.enter_actions, state)
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// The names of the outputs produced when leaving the state.
+    pub fn exit_actions_of(&self, state: &Ident) -> Vec<String> {
+        crate::actions_of(self.exit_actions, state)
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// The states and superstates that are not contained in any superstate.
+    pub fn top_level_states(&self) -> impl Iterator<Item = &Ident> {
+        self.states
+            .iter()
+            .filter(|state| self.hierarchy.parent(state).is_none())
+            .chain(self.hierarchy.roots())
+    }
+}
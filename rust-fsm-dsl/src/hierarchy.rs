@@ -0,0 +1,111 @@
+use syn::{
+    parse::{Error, ParseStream, Result},
+    *,
+};
+
+/// Superstates declared as `state Parent { Child1, Child2 { Grandchild } }`.
+/// Only the leaves of this tree are the actual states of the machine.
+#[derive(Default)]
+pub struct Hierarchy {
+    /// `(child, parent)` pairs in the order of declaration.
+    parents: Vec<(Ident, Ident)>,
+}
+
+impl Hierarchy {
+    /// Parses the children of the `parent` superstate: `{ Child1, Child2 { ... } }`.
+    pub fn parse_children(&mut self, input: ParseStream, parent: &Ident) -> Result<()> {
+        let content;
+        braced!(content in input);
+        if content.is_empty() {
+            return Err(Error::new_spanned(
+                parent,
+                "a superstate must contain at least one state",
+            ));
+        }
+        while !content.is_empty() {
+            let child: Ident = content.parse()?;
+            if let Some(other) = self.parent(&child) {
+                return Err(Error::new_spanned(
+                    &child,
+                    format!("state `{child}` is already a part of `{other}`"),
+                ));
+            }
+            self.parents.push((child.clone(), parent.clone()));
+            if content.peek(token::Brace) {
+                self.parse_children(&content, &child)?;
+            }
+            if content.is_empty() {
+                break;
+            }
+            content.parse::<Token![,]>()?;
+        }
+        Ok(())
+    }
+
+    pub fn parent(&self, state: &Ident) -> Option<&Ident> {
+        self.parents
+            .iter()
+            .find(|(child, _)| child == state)
+            .map(|(_, parent)| parent)
+    }
+
+    /// All superstates containing `state`, the closest one first.
+    pub fn ancestors<'a>(&'a self, state: &'a Ident) -> Vec<&'a Ident> {
+        std::iter::successors(self.parent(state), |x| self.parent(x)).collect()
+    }
+
+    pub fn is_superstate(&self, state: &Ident) -> bool {
+        self.parents.iter().any(|(_, parent)| parent == state)
+    }
+
+    /// Top-level superstates in the order of declaration.
+    pub fn roots(&self) -> Vec<&Ident> {
+        let mut roots: Vec<&Ident> = vec![];
+        for (_, parent) in &self.parents {
+            if self.parent(parent).is_none() && !roots.contains(&parent) {
+                roots.push(parent);
+            }
+        }
+        roots
+    }
+
+    pub fn children<'a>(&'a self, state: &'a Ident) -> impl Iterator<Item = &'a Ident> {
+        self.parents
+            .iter()
+            .filter(move |(_, parent)| parent == state)
+            .map(|(child, _)| child)
+    }
+
+    /// The actual states contained in the `state` superstate with the distance
+    /// to them.
+    pub fn leaves<'a>(&'a self, state: &'a Ident) -> Vec<(&'a Ident, usize)> {
+        self.children(state)
+            .flat_map(|child| {
+                if self.is_superstate(child) {
+                    self.leaves(child)
+                        .into_iter()
+                        .map(|(leaf, distance)| (leaf, distance + 1))
+                        .collect()
+                } else {
+                    vec![(child, 1)]
+                }
+            })
+            .collect()
+    }
+
+    /// All the actual states declared in superstates.
+    pub fn all_leaves(&self) -> impl Iterator<Item = &Ident> {
+        self.parents
+            .iter()
+            .map(|(child, _)| child)
+            .filter(|child| !self.is_superstate(child))
+    }
+
+    /// The state entered when transitioning into `state`: the first declared
+    /// child (recursively) for superstates and `state` itself otherwise.
+    pub fn initial_leaf<'a>(&'a self, state: &'a Ident) -> &'a Ident {
+        self.children(state)
+            .next()
+            .map_or(state, |child| self.initial_leaf(child))
+    }
+}
@@ -0,0 +1,233 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream, Result},
+    *,
+};
+
+use crate::{parser::StateMachineDef, variant::Final};
+
+mod mermaid;
+mod scxml;
+
+/// The arguments of `state_machine_from_file`: the path to the file, optionally
+/// followed by the header of the machine, e.g.
+/// `"door.mmd", #[derive(Debug)] pub Door => pub Action => pub Effect`.
+pub struct FromFileDef {
+    path: LitStr,
+    header: Option<TokenStream>,
+}
+
+impl Parse for FromFileDef {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.parse()?;
+        let header = if input.is_empty() {
+            None
+        } else {
+            input.parse::<Token![,]>()?;
+            Some(input.parse()?)
+        };
+        Ok(Self { path, header })
+    }
+}
+
+/// A transition read from a file.
+pub struct Transition {
+    pub from: String,
+    pub input: String,
+    /// `None` for the transitions that keep the current state.
+    pub to: Option<String>,
+    pub outputs: Vec<String>,
+}
+
+/// The definition of a machine read from a file, using the names of the states,
+/// inputs and outputs as written there.
+#[derive(Default)]
+pub struct Definition {
+    pub name: Option<String>,
+    pub initial_state: Option<String>,
+    pub transitions: Vec<Transition>,
+    pub final_states: Vec<String>,
+    pub enter_actions: Vec<(String, String)>,
+    pub exit_actions: Vec<(String, String)>,
+    /// Superstates with their children in the order of declaration, the
+    /// initial child first.
+    pub superstates: Vec<(String, Vec<String>)>,
+}
+
+/// A problem found in a file with the line it is on.
+pub struct ImportError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ImportError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+/// Checks that the name can be used as an identifier in the generated code.
+pub fn check_ident(name: &str, line: usize) -> std::result::Result<String, ImportError> {
+    match syn::parse_str::<Ident>(name) {
+        Ok(_) => Ok(name.to_string()),
+        Err(_) => Err(ImportError::new(
+            line,
+            format!("`{name}` is not a valid Rust identifier"),
+        )),
+    }
+}
+
+impl Definition {
+    fn write_superstate(&self, dsl: &mut String, superstate: &str) {
+        let children = self
+            .superstates
+            .iter()
+            .find(|(x, _)| x == superstate)
+            .map_or(&[][..], |(_, children)| children);
+        dsl.push_str(superstate);
+        if !children.is_empty() {
+            dsl.push_str(" { ");
+            for (i, child) in children.iter().enumerate() {
+                if i > 0 {
+                    dsl.push_str(", ");
+                }
+                self.write_superstate(dsl, child);
+            }
+            dsl.push_str(" }");
+        }
+    }
+
+    /// Writes the body of the definition in the syntax of `state_machine`.
+    fn to_dsl(&self) -> String {
+        let outputs = |outputs: &[String]| {
+            if outputs.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", outputs.join(", "))
+            }
+        };
+        let mut items = vec![];
+        for transition in &self.transitions {
+            items.push(format!(
+                "{} => {} => {}{}",
+                transition.from,
+                transition.input,
+                transition.to.as_deref().unwrap_or("_"),
+                outputs(&transition.outputs),
+            ));
+        }
+        for state in &self.final_states {
+            items.push(format!("final {state}"));
+        }
+        for (state, action) in &self.enter_actions {
+            items.push(format!("enter {state} [{action}]"));
+        }
+        for (state, action) in &self.exit_actions {
+            items.push(format!("exit {state} [{action}]"));
+        }
+        let is_child = |state: &String| {
+            self.superstates
+                .iter()
+                .any(|(_, children)| children.contains(state))
+        };
+        for (superstate, _) in self.superstates.iter().filter(|(x, _)| !is_child(x)) {
+            let mut item = "state ".to_string();
+            self.write_superstate(&mut item, superstate);
+            items.push(item);
+        }
+        items.join(",\n")
+    }
+}
+
+/// Turns the name of a file into a type name: `circuit_breaker` becomes
+/// `CircuitBreaker`.
+fn type_name(stem: &str) -> String {
+    stem.split(|c: char| !c.is_alphanumeric())
+        .filter(|x| !x.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|x| x.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Reads the definition of the machine from the file. The format is chosen by
+/// the extension of the file.
+fn read(path: &Path) -> std::result::Result<Definition, ImportError> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|error| ImportError::new(0, format!("cannot read the file: {error}")))?;
+    match path.extension().and_then(|x| x.to_str()) {
+        Some("scxml" | "xml") => scxml::parse(&source),
+        Some("mmd" | "mermaid") => mermaid::parse(&source),
+        _ => Err(ImportError::new(
+            0,
+            "unknown file format, expected a `.scxml`, `.xml`, `.mmd` or `.mermaid` file",
+        )),
+    }
+}
+
+pub fn state_machine_from_file(def: FromFileDef) -> Result<TokenStream> {
+    let FromFileDef { path, header } = def;
+    let full_path = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join(path.value());
+    let definition = read(&full_path).map_err(|error| {
+        let location = match error.line {
+            0 => path.value(),
+            line => format!("{}:{line}", path.value()),
+        };
+        Error::new_spanned(&path, format!("rust-fsm: {location}: {}", error.message))
+    })?;
+
+    let header = match header {
+        Some(header) => header,
+        None => {
+            let name = definition.name.clone().unwrap_or_else(|| {
+                type_name(
+                    &full_path
+                        .file_stem()
+                        .map(|x| x.to_string_lossy())
+                        .unwrap_or_default(),
+                )
+            });
+            let name = syn::parse_str::<Ident>(&name).map_err(|_| {
+                Error::new_spanned(
+                    &path,
+                    format!(
+                        "rust-fsm: cannot name the machine `{name}`, provide the header \
+                         of the machine after the path"
+                    ),
+                )
+            })?;
+            let input = format_ident!("{name}Input");
+            let output = format_ident!("{name}Output");
+            quote!(#name => #input => #output)
+        }
+    };
+    let body = TokenStream::from_str(&definition.to_dsl())?;
+    let mut def: StateMachineDef = parse2(quote!(#header #body))?;
+    if def.initial_state.is_none() {
+        def.initial_state = definition
+            .initial_state
+            .map(|x| Final::from(Ident::new(&x, path.span())));
+    }
+
+    // Rebuild the crate when the file changes.
+    let full_path = full_path.to_string_lossy();
+    let machine = crate::generate(def);
+    Ok(quote! {
+        const _: &str = include_str!(#full_path);
+        #machine
+    })
+}
@@ -0,0 +1,230 @@
+use super::{check_ident, Definition, ImportError, Transition};
+
+type Result<T> = std::result::Result<T, ImportError>;
+
+/// Decodes the `#NN;` entity codes the Mermaid diagrams use to escape
+/// characters in labels.
+fn unescape(label: &str) -> String {
+    let mut result = String::new();
+    let mut rest = label;
+    while let Some(start) = rest.find('#') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest[1..].split_once(';').and_then(|(code, tail)| {
+            let c = code.parse().ok().and_then(char::from_u32)?;
+            Some((c, tail))
+        });
+        match decoded {
+            Some((c, tail)) => {
+                result.push(c);
+                rest = tail;
+            }
+            None => {
+                result.push('#');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Parses the label of a transition: `Input`, `Input / Output1, Output2` or
+/// `Input [Output1, Output2]`, where the outputs may be quoted.
+fn parse_label(label: &str, line: usize) -> Result<(String, Vec<String>)> {
+    let label = unescape(label);
+    let (input, outputs) = if let Some((input, outputs)) = label.split_once('/') {
+        (input, Some(outputs))
+    } else if let Some((input, outputs)) = label.split_once('[') {
+        let outputs = outputs.trim_end().strip_suffix(']').ok_or_else(|| {
+            ImportError::new(line, format!("unterminated list of outputs in `{label}`"))
+        })?;
+        (input, Some(outputs))
+    } else {
+        (label.as_str(), None)
+    };
+    let input = check_ident(input.trim(), line)?;
+    let outputs = outputs
+        .into_iter()
+        .flat_map(|x| x.split(','))
+        .map(|x| check_ident(x.trim().trim_matches('"'), line))
+        .collect::<Result<_>>()?;
+    Ok((input, outputs))
+}
+
+/// Reads a Mermaid `stateDiagram-v2`. The transitions must be labelled with
+/// their inputs. Styling, directions and notes are ignored, while the
+/// constructs that cannot be expressed in the DSL (concurrency, choices, forks,
+/// state descriptions) are reported as errors.
+pub fn parse(source: &str) -> Result<Definition> {
+    let mut definition = Definition::default();
+    // The superstates being read.
+    let mut stack: Vec<usize> = vec![];
+    // The states already placed in the hierarchy.
+    let mut seen: Vec<String> = vec![];
+    let mut header = false;
+    let mut in_note = false;
+
+    let mut mention = |definition: &mut Definition, stack: &[usize], state: &str| {
+        if !seen.iter().any(|x| x == state) {
+            seen.push(state.to_string());
+            if let Some(&parent) = stack.last() {
+                definition.superstates[parent].1.push(state.to_string());
+            }
+        }
+    };
+
+    for (index, text) in source.lines().enumerate() {
+        let line = index + 1;
+        let text = text.trim();
+        if text.is_empty() || text.starts_with("%%") {
+            continue;
+        }
+        if in_note {
+            in_note = text != "end note";
+            continue;
+        }
+        if !header {
+            if text != "stateDiagram-v2" && text != "stateDiagram" {
+                return Err(ImportError::new(line, "expected `stateDiagram-v2`"));
+            }
+            header = true;
+            continue;
+        }
+
+        let keyword = text.split_whitespace().next().unwrap_or_default();
+        if text == "}" {
+            if stack.pop().is_none() {
+                return Err(ImportError::new(line, "unexpected `}`"));
+            }
+        } else if [
+            "direction",
+            "classDef",
+            "class",
+            "style",
+            "accTitle",
+            "accDescr",
+        ]
+        .contains(&keyword)
+        {
+            continue;
+        } else if keyword == "note" {
+            in_note = !text.contains(':');
+        } else if text == "--" {
+            return Err(ImportError::new(
+                line,
+                "concurrent states are not supported",
+            ));
+        } else if text.contains("<<") {
+            return Err(ImportError::new(
+                line,
+                "choices, forks and joins are not supported",
+            ));
+        } else if let Some(rest) = text.strip_prefix("state ") {
+            let (name, composite) = match rest.strip_suffix('{') {
+                Some(name) => (name.trim(), true),
+                None => (rest.trim(), false),
+            };
+            if name.contains(' ') || name.contains('"') {
+                return Err(ImportError::new(
+                    line,
+                    "state descriptions are not supported",
+                ));
+            }
+            let name = check_ident(name, line)?;
+            mention(&mut definition, &stack, &name);
+            if composite {
+                if definition.superstates.iter().any(|(x, _)| x == &name) {
+                    return Err(ImportError::new(
+                        line,
+                        format!("superstate `{name}` is declared more than once"),
+                    ));
+                }
+                definition.superstates.push((name, vec![]));
+                stack.push(definition.superstates.len() - 1);
+            }
+        } else if let Some((from, rest)) = text.split_once("-->") {
+            let (from, (to, label)) = (
+                from.trim(),
+                match rest.split_once(':') {
+                    Some((to, label)) => (to.trim(), Some(label)),
+                    None => (rest.trim(), None),
+                },
+            );
+            if from == "[*]" {
+                let to = check_ident(to, line)?;
+                match stack.last() {
+                    // The initial state of a superstate is its first child.
+                    Some(&parent) => {
+                        mention(&mut definition, &stack, &to);
+                        let children = &mut definition.superstates[parent].1;
+                        if let Some(position) = children.iter().position(|x| x == &to) {
+                            let child = children.remove(position);
+                            children.insert(0, child);
+                        }
+                    }
+                    None => definition.initial_state = Some(to),
+                }
+                continue;
+            }
+            let from = check_ident(from, line)?;
+            if to == "[*]" {
+                definition.final_states.push(from);
+                continue;
+            }
+            let to = match to {
+                "_" => None,
+                to => Some(check_ident(to, line)?),
+            };
+            let Some(label) = label else {
+                return Err(ImportError::new(
+                    line,
+                    "the transition must be labelled with its input",
+                ));
+            };
+            let (input, outputs) = parse_label(label, line)?;
+            mention(&mut definition, &stack, &from);
+            if let Some(to) = &to {
+                mention(&mut definition, &stack, to);
+            }
+            definition.transitions.push(Transition {
+                from,
+                input,
+                to,
+                outputs,
+            });
+        } else if let Some((state, description)) = text.split_once(':') {
+            let state = check_ident(state.trim(), line)?;
+            let description = unescape(description);
+            let (kind, action) = description
+                .split_once('/')
+                .map(|(x, y)| (x.trim(), y.trim()))
+                .unwrap_or_default();
+            let actions = match kind {
+                "enter" | "entry" => &mut definition.enter_actions,
+                "exit" => &mut definition.exit_actions,
+                _ => {
+                    return Err(ImportError::new(
+                        line,
+                        "state descriptions other than `enter / Output` and `exit / Output` \
+                         are not supported",
+                    ))
+                }
+            };
+            actions.push((state, check_ident(action, line)?));
+        } else {
+            let state = check_ident(text, line)?;
+            mention(&mut definition, &stack, &state);
+        }
+    }
+    if !header {
+        return Err(ImportError::new(0, "the diagram is empty"));
+    }
+    if !stack.is_empty() {
+        return Err(ImportError::new(
+            source.lines().count(),
+            "unterminated composite state",
+        ));
+    }
+    Ok(definition)
+}
@@ -0,0 +1,299 @@
+use super::{check_ident, Definition, ImportError, Transition};
+
+type Result<T> = std::result::Result<T, ImportError>;
+
+/// An XML element with the line it starts on.
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    line: usize,
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(x, _)| x == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// An attribute holding a single identifier.
+    fn ident(&self, name: &str) -> Result<Option<String>> {
+        self.attribute(name)
+            .map(|value| {
+                if value.split_whitespace().count() > 1 {
+                    return Err(ImportError::new(
+                        self.line,
+                        format!("multiple values of `{name}` are not supported"),
+                    ));
+                }
+                check_ident(value.trim(), self.line)
+            })
+            .transpose()
+    }
+
+    fn required_ident(&self, name: &str) -> Result<String> {
+        self.ident(name)?.ok_or_else(|| {
+            ImportError::new(
+                self.line,
+                format!("`<{}>` must have the `{name}` attribute", self.name),
+            )
+        })
+    }
+
+    fn unsupported(&self) -> ImportError {
+        ImportError::new(
+            self.line,
+            format!("`<{}>` is not supported here", self.name),
+        )
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// A minimal reader for the subset of XML used by SCXML documents without
+/// executable content: elements, attributes, comments and declarations.
+struct Reader<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn line(&self) -> usize {
+        self.source[..self.position].matches('\n').count() + 1
+    }
+
+    fn rest(&self) -> &str {
+        &self.source[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<()> {
+        let line = self.line();
+        match self.rest().find(end) {
+            Some(x) => {
+                self.position += x + end.len();
+                Ok(())
+            }
+            None => Err(ImportError::new(line, format!("expected `{end}`"))),
+        }
+    }
+
+    /// Skips whitespace, comments, declarations and processing instructions.
+    fn skip_misc(&mut self) -> Result<()> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!") && !self.rest().starts_with("<![CDATA[") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> String {
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| c.is_whitespace() || c == '/' || c == '>' || c == '=')
+            .unwrap_or(rest.len());
+        let name = rest[..length].to_string();
+        self.position += length;
+        name
+    }
+
+    fn element(&mut self) -> Result<Element> {
+        let line = self.line();
+        if !self.rest().starts_with('<') {
+            return Err(ImportError::new(line, "text content is not supported"));
+        }
+        self.position += 1;
+        let name = self.name();
+        let mut attributes = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.position += 2;
+                return Ok(Element {
+                    name,
+                    attributes,
+                    children: vec![],
+                    line,
+                });
+            }
+            if self.rest().starts_with('>') {
+                self.position += 1;
+                break;
+            }
+            let attribute = self.name();
+            self.skip_whitespace();
+            let quote = self
+                .rest()
+                .strip_prefix('=')
+                .map(str::trim_start)
+                .and_then(|x| x.chars().next())
+                .filter(|x| *x == '"' || *x == '\'');
+            let (Some(quote), false) = (quote, attribute.is_empty()) else {
+                return Err(ImportError::new(self.line(), "malformed attribute"));
+            };
+            self.position += self.rest().find(quote).unwrap() + 1;
+            let Some(length) = self.rest().find(quote) else {
+                return Err(ImportError::new(
+                    self.line(),
+                    "unterminated attribute value",
+                ));
+            };
+            attributes.push((attribute, unescape(&self.rest()[..length])));
+            self.position += length + 1;
+        }
+        let mut children = vec![];
+        loop {
+            self.skip_misc()?;
+            if self.rest().is_empty() {
+                return Err(ImportError::new(line, format!("`<{name}>` is not closed")));
+            }
+            if let Some(rest) = self.rest().strip_prefix("</") {
+                let closing = rest.split('>').next().unwrap_or_default().trim();
+                if closing != name {
+                    return Err(ImportError::new(
+                        self.line(),
+                        format!("expected `</{name}>`"),
+                    ));
+                }
+                self.skip_past(">")?;
+                return Ok(Element {
+                    name,
+                    attributes,
+                    children,
+                    line,
+                });
+            }
+            children.push(self.element()?);
+        }
+    }
+}
+
+/// Reads the outputs sent by the `<send>` and `<raise>` elements.
+fn outputs(parent: &Element) -> Result<Vec<String>> {
+    parent
+        .children
+        .iter()
+        .map(|element| match element.name.as_str() {
+            "send" | "raise" if element.children.is_empty() => element.required_ident("event"),
+            _ => Err(element.unsupported()),
+        })
+        .collect()
+}
+
+fn read_state(definition: &mut Definition, state: &Element) -> Result<()> {
+    let id = state.required_ident("id")?;
+    if state.name == "final" {
+        definition.final_states.push(id.clone());
+    }
+    let mut children = vec![];
+    for element in &state.children {
+        match (state.name.as_str(), element.name.as_str()) {
+            (_, "onentry") => {
+                for output in outputs(element)? {
+                    definition.enter_actions.push((id.clone(), output));
+                }
+            }
+            (_, "onexit") => {
+                for output in outputs(element)? {
+                    definition.exit_actions.push((id.clone(), output));
+                }
+            }
+            ("state", "transition") => {
+                if element.attribute("cond").is_some() {
+                    return Err(ImportError::new(
+                        element.line,
+                        "conditions are not supported, as the inputs cannot carry data",
+                    ));
+                }
+                definition.transitions.push(Transition {
+                    from: id.clone(),
+                    input: element.required_ident("event")?,
+                    to: element.ident("target")?,
+                    outputs: outputs(element)?,
+                });
+            }
+            ("state", "state" | "final") => {
+                children.push(element.required_ident("id")?);
+                read_state(definition, element)?;
+            }
+            _ => return Err(element.unsupported()),
+        }
+    }
+    if let Some(initial) = state.ident("initial")? {
+        let Some(position) = children.iter().position(|x| x == &initial) else {
+            return Err(ImportError::new(
+                state.line,
+                format!("`{initial}` is not a child of `{id}`"),
+            ));
+        };
+        let child = children.remove(position);
+        children.insert(0, child);
+    }
+    if !children.is_empty() {
+        definition.superstates.push((id, children));
+    }
+    Ok(())
+}
+
+/// Reads a SCXML document. Only the states, the transitions and the events
+/// sent or raised by them are supported, the other executable content and the
+/// data model are reported as errors.
+pub fn parse(source: &str) -> Result<Definition> {
+    let mut reader = Reader {
+        source,
+        position: 0,
+    };
+    reader.skip_misc()?;
+    let root = reader.element()?;
+    reader.skip_misc()?;
+    if !reader.rest().is_empty() {
+        return Err(ImportError::new(
+            reader.line(),
+            "unexpected content after the document",
+        ));
+    }
+    if root.name != "scxml" {
+        return Err(ImportError::new(root.line, "expected `<scxml>`"));
+    }
+
+    let mut definition = Definition {
+        name: root.ident("name")?,
+        initial_state: root.ident("initial")?,
+        ..Definition::default()
+    };
+    for element in &root.children {
+        match element.name.as_str() {
+            "state" | "final" => read_state(&mut definition, element)?,
+            _ => return Err(element.unsupported()),
+        }
+    }
+    if definition.initial_state.is_none() {
+        // The first state is the initial one by default.
+        definition.initial_state = root
+            .children
+            .first()
+            .map(|x| x.required_ident("id"))
+            .transpose()?;
+    }
+    Ok(definition)
+}
@@ -7,18 +7,75 @@ extern crate proc_macro;
 use proc_macro::TokenStream;
 use quote::{quote, ToTokens};
 use syn::*;
+mod analysis;
+#[cfg(feature = "dot")]
+mod dot;
+mod graph;
+mod hierarchy;
+mod import;
+mod mermaid;
 mod parser;
+#[cfg(feature = "plantuml")]
+mod plantuml;
+#[cfg(feature = "scxml")]
+mod scxml;
+#[cfg(feature = "serde")]
+mod serde;
 mod variant;
 use variant::Variant;
 
-use crate::{parser::StateMachineDef, variant::Final};
+use crate::{
+    hierarchy::Hierarchy,
+    parser::{Source, StateMachineDef},
+    variant::Final,
+};
 /// The full information about a state transition. Used to unify the
-/// represantion of the simple and the compact forms.
+/// represantion of the simple and the compact forms, and of the transitions
+/// inherited from superstates.
 struct Transition<'a> {
-    initial_state: &'a Variant,
+    initial_state: Variant,
+    /// Whether the transition is declared for a superstate of `initial_state`.
+    inherited: bool,
     input_value: &'a Variant,
-    final_state: &'a Final,
-    output: &'a Option<Final>,
+    final_state: Final,
+    outputs: &'a [Final],
+}
+
+/// The entry or exit actions declared for the given state.
+fn actions_of<'a>(
+    actions: &'a [(Ident, Final)],
+    state: &'a Ident,
+) -> impl Iterator<Item = &'a Final> {
+    actions
+        .iter()
+        .filter(move |(x, _)| x == state)
+        .map(|(_, action)| action)
+}
+
+/// Replaces a superstate with the state entered when transitioning into it.
+fn resolve_state(hierarchy: &Hierarchy, state: &Final) -> Final {
+    match state.variant_ref() {
+        Some(x) if hierarchy.is_superstate(&x.ident) => {
+            Final::from(hierarchy.initial_leaf(&x.ident).clone())
+        }
+        _ => state.clone(),
+    }
+}
+
+/// Combines the errors into one to report all of them at once.
+fn combine_errors(errors: impl IntoIterator<Item = Error>) -> Option<Error> {
+    errors.into_iter().reduce(|mut errors, error| {
+        errors.combine(error);
+        errors
+    })
+}
+
+/// The 64-bit FNV-1a hash of the definition, which does not depend on the
+/// version of the compiler, unlike the hashers of the standard library.
+fn definition_hash(definition: &str) -> u64 {
+    definition.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
 }
 
 fn attrs_to_token_stream(attrs: Vec<Attribute>) -> proc_macro2::TokenStream {
@@ -30,13 +87,33 @@ fn attrs_to_token_stream(attrs: Vec<Attribute>) -> proc_macro2::TokenStream {
 /// Produce a state machine definition from the provided `rust-fmt` DSL
 /// description.
 pub fn state_machine(tokens: TokenStream) -> TokenStream {
+    generate(parse_macro_input!(tokens as parser::StateMachineDef)).into()
+}
+
+#[proc_macro]
+/// Produce a state machine definition from a SCXML document or a Mermaid state
+/// diagram read from a file.
+pub fn state_machine_from_file(tokens: TokenStream) -> TokenStream {
+    import::state_machine_from_file(parse_macro_input!(tokens as import::FromFileDef))
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Generates the state machine from its definition.
+fn generate(def: StateMachineDef) -> proc_macro2::TokenStream {
     let StateMachineDef {
         doc,
+        options,
         state_name: (state_attrs, state_visibility, state_name),
+        initial_state,
         input_name: (input_attrs, input_visibility, input_name),
         output_name: (output_attrs, output_visibility, output_name),
         transitions,
-    } = parse_macro_input!(tokens as parser::StateMachineDef);
+        final_states,
+        enter_actions,
+        exit_actions,
+        hierarchy,
+    } = def;
 
     let doc = attrs_to_token_stream(doc);
 
@@ -44,74 +121,279 @@ pub fn state_machine(tokens: TokenStream) -> TokenStream {
         let output = quote! {
             compile_error!("rust-fsm: at least one state transition must be provided");
         };
-        return output.into();
+        return output;
     }
 
-    let transitions = transitions.iter().flat_map(|def| {
-        def.transitions.iter().map(move |transition| Transition {
-            initial_state: &def.initial_state,
-            input_value: &transition.input_value,
-            final_state: &transition.final_state,
-            output: &transition.output,
+    let final_states = final_states
+        .into_iter()
+        .flat_map(|state| {
+            if hierarchy.is_superstate(&state) {
+                hierarchy
+                    .leaves(&state)
+                    .into_iter()
+                    .map(|(leaf, _)| leaf.clone())
+                    .collect()
+            } else {
+                vec![state]
+            }
         })
-    });
-    // fn id(x: impl std::hash::Hash) -> u64 {
-    //     use std::hash::BuildHasher;
-    //     rustc_hash::FxSeededState::with_seed(5).hash_one(x)
-    // }
+        .collect::<Vec<_>>();
+
+    if let Some(error) = combine_errors(transitions.iter().filter_map(|def| {
+        let Source::State(initial_state) = &def.source else {
+            return None;
+        };
+        let state = &initial_state.ident;
+        if final_states.contains(state) {
+            Some(format!(
+                "rust-fsm: final state `{state}` cannot have outgoing transitions"
+            ))
+        } else if hierarchy.is_superstate(state) && initial_state.field.is_some() {
+            Some(format!("rust-fsm: superstate `{state}` cannot carry data"))
+        } else {
+            None
+        }
+        .map(|message| Error::new_spanned(state, message))
+    })) {
+        return error.into_compile_error();
+    }
+
+    let (duplicates, shadowed) = analysis::find_duplicates(&transitions);
+    if let Some(error) = combine_errors(duplicates) {
+        return error.into_compile_error();
+    }
+
+    let initial_state = initial_state.map(|x| resolve_state(&hierarchy, &x));
+
     let mut states = vec![];
     let mut inputs = vec![];
     let mut outputs = vec![];
-    let mut transition_cases = vec![];
 
-    #[cfg(feature = "diagram")]
-    let mut mermaid_diagram = format!(
-        "///```mermaid
-///stateDiagram-v2\n",
+    if let Some(initial_state) = &initial_state {
+        states.extend(initial_state.clone().variant());
+    }
+    for def in &transitions {
+        for transition in &def.transitions {
+            let final_state = &transition.final_state;
+            let input_value = &transition.input_value;
+
+            if let Source::State(initial_state) = &def.source {
+                // Superstates are not states of the machine by themselves.
+                if !hierarchy.is_superstate(&initial_state.ident) {
+                    states.push(Variant::clone(initial_state));
+                }
+            }
+            states.extend(
+                final_state
+                    .variant_ref()
+                    .filter(|x| !hierarchy.is_superstate(&x.ident))
+                    .cloned(),
+            );
+            inputs.push(input_value.clone());
+            outputs.extend(
+                transition
+                    .outputs
+                    .iter()
+                    .filter_map(|output| output.clone().variant()),
+            );
+        }
+    }
+
+    states.extend(
+        final_states
+            .iter()
+            .chain(hierarchy.all_leaves())
+            .map(|ident| Variant {
+                ident: ident.clone(),
+                field: None,
+            }),
     );
-    for transition in transitions {
+    let known_states = variant::idents(&states)
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if let Some(error) = combine_errors(
+        transitions
+            .iter()
+            .flat_map(|def| match &def.source {
+                Source::Any(except) => except.as_slice(),
+                Source::State(_) => &[],
+            })
+            .filter(|state| !known_states.contains(state) && !hierarchy.is_superstate(state))
+            .map(|state| Error::new_spanned(state, format!("rust-fsm: unknown state `{state}`"))),
+    ) {
+        return error.into_compile_error();
+    }
+
+    // The states a `_` transition starts from: all the states that are not
+    // final and not excluded either directly or via a superstate.
+    let any_sources = |except: &[Ident]| {
+        known_states
+            .iter()
+            .filter(|state| {
+                !final_states.contains(state)
+                    && !except.contains(state)
+                    && !hierarchy
+                        .ancestors(state)
+                        .iter()
+                        .any(|x| except.contains(x))
+            })
+            .map(|state| Variant {
+                ident: state.clone(),
+                field: None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // The transitions as shown in the diagrams: the `_` transitions go last.
+    let (declared, any): (Vec<_>, Vec<_>) = transitions
+        .iter()
+        .partition(|def| matches!(def.source, Source::State(_)));
+    // Whether a transition declared for the state or one of its superstates is
+    // always taken on the input, so the `_` transitions on it never are.
+    let always_handled = |state: &Ident, input: &Ident| {
+        declared.iter().any(|def| {
+            matches!(&def.source, Source::State(source) if source.is_irrefutable()
+                && (&source.ident == state || hierarchy.ancestors(state).contains(&&source.ident)))
+                && def.transitions.iter().any(|transition| {
+                    &transition.input_value.ident == input
+                        && transition.input_value.is_irrefutable()
+                })
+        })
+    };
+    let graph_edges = declared
+        .iter()
+        .copied()
+        .chain(any)
+        .flat_map(|def| {
+            let (sources, any) = match &def.source {
+                Source::State(initial_state) => (vec![Variant::clone(initial_state)], false),
+                Source::Any(except) => (any_sources(except), true),
+            };
+            sources.into_iter().flat_map(move |from| {
+                let state = from.ident.clone();
+                def.transitions
+                    .iter()
+                    .filter(move |transition| {
+                        !any || !always_handled(&state, &transition.input_value.ident)
+                    })
+                    .map(move |transition| {
+                        let (pattern, guard) = transition.input_value.separate();
+                        let guard = from
+                            .separate()
+                            .1
+                            .into_iter()
+                            .chain(guard)
+                            .map(|x| x.to_string())
+                            .reduce(|x, y| format!("{x} && {y}"));
+                        graph::Edge {
+                            from: from.ident.clone(),
+                            to: transition
+                                .final_state
+                                .variant_ref()
+                                .map(|x| x.ident.clone()),
+                            label: transition.input_value.match_on().to_string(),
+                            input: transition.input_value.ident.clone(),
+                            pattern: pattern.to_string(),
+                            guard,
+                            outputs: transition.outputs.iter().map(ToString::to_string).collect(),
+                        }
+                    })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Transitions of a superstate are inherited by all the states it contains,
+    // and `_` transitions by all the states of the machine.
+    let mut expanded = transitions
+        .iter()
+        .flat_map(|def| {
+            let sources = match &def.source {
+                Source::State(initial_state) if hierarchy.is_superstate(&initial_state.ident) => {
+                    hierarchy
+                        .leaves(&initial_state.ident)
+                        .into_iter()
+                        .map(|(leaf, distance)| {
+                            let leaf = Variant {
+                                ident: leaf.clone(),
+                                field: None,
+                            };
+                            (leaf, distance)
+                        })
+                        .collect()
+                }
+                Source::State(initial_state) => vec![(Variant::clone(initial_state), 0)],
+                Source::Any(except) => any_sources(except)
+                    .into_iter()
+                    .map(|state| (state, usize::MAX))
+                    .collect(),
+            };
+            let hierarchy = &hierarchy;
+            sources
+                .into_iter()
+                .flat_map(move |(initial_state, distance)| {
+                    def.transitions.iter().map(move |transition| {
+                        let transition = Transition {
+                            initial_state: initial_state.clone(),
+                            inherited: distance > 0,
+                            input_value: &transition.input_value,
+                            final_state: resolve_state(hierarchy, &transition.final_state),
+                            outputs: &transition.outputs,
+                        };
+                        (distance, transition)
+                    })
+                })
+        })
+        .collect::<Vec<_>>();
+    // The transitions of a state take precedence over the inherited ones, the
+    // transitions of closer superstates take precedence over the others, and
+    // `_` transitions are tried last.
+    expanded.sort_by_key(|(distance, _)| *distance);
+
+    let mut transition_cases = vec![];
+    let mut edges = vec![];
+    let mut transition_infos = vec![];
+    // The transitions as text, hashed to identify the definition.
+    let mut definition = String::new();
+    let mut query_cases = vec![];
+    let mut max_outputs = 0;
+    for (_, transition) in expanded {
         let Transition {
             initial_state,
+            inherited,
             final_state,
             input_value,
-            output,
+            outputs: transition_outputs,
         } = transition;
 
-        // #[cfg(feature = "diagram")]
-        // writeln!(
-        //     mermaid_diagram,
-        //     "///    {}: {initial_state}",
-        //     id(&initial_state)
-        // )
-        // .unwrap();
-        // #[cfg(feature = "diagram")]
-        // writeln!(
-        //     mermaid_diagram,
-        //     "///    {}: {final_state}",
-        //     id(&final_state)
-        // )
-        // .unwrap();
-        use std::fmt::Write;
-        #[cfg(feature = "diagram")]
-        write!(
-            mermaid_diagram,
-            "///    {}",
-            &format!(
-                "{:?}",
-                format!(
-                    "{initial_state} --> {final_state}: {}",
-                    input_value.match_on()
-                )
-            )
-            .trim_matches('"'),
-        )
-        .unwrap();
+        edges.push(analysis::Edge {
+            from: initial_state.ident.clone(),
+            to: final_state.variant_ref().map(|x| x.ident.clone()),
+            input: input_value.ident.clone(),
+            refutable: !initial_state.is_irrefutable() || !input_value.is_irrefutable(),
+        });
 
         let (initial_, guard_) = initial_state.separate();
-        let final_ = final_state
-            .reduce()
-            .map_or(initial_state.match_on(), |x| quote! { #x });
         let (input_, guard) = input_value.separate();
+
+        // Queries only borrow the state and the input, so the guards can only
+        // be evaluated on references to the data, which not every guard
+        // compiles with. Unless requested, they are not evaluated at all.
+        let query_guard = guard_
+            .iter()
+            .chain(&guard)
+            .cloned()
+            .reduce(|x, y| quote! { #x && #y })
+            .filter(|_| options.guards_by_ref)
+            .map(|guard| quote! { if #guard });
+        let query_state = if inherited {
+            let ident = &initial_state.ident;
+            quote! { Self::#ident { .. } }
+        } else {
+            quote! { Self::#initial_ }
+        };
+        query_cases.push((query_state, input_.clone(), query_guard));
         let guard = guard_
             .clone()
             .zip(guard.clone())
@@ -120,99 +402,404 @@ pub fn state_machine(tokens: TokenStream) -> TokenStream {
             })
             .or(guard_.or(guard).map(|x| quote! { if #x }))
             .unwrap_or_default();
+        let (pattern, final_) = match (final_state.reduce(), inherited) {
+            (Some(x), false) => (quote! { Self::#initial_ }, quote! { Self::#x }),
+            (None, false) => (quote! { Self::#initial_ }, quote! { Self::#initial_ }),
+            // Inherited transitions match states carrying any data
+            (Some(x), true) => {
+                let ident = &initial_state.ident;
+                (quote! { Self::#ident { .. } }, quote! { Self::#x })
+            }
+            (None, true) => {
+                let ident = &initial_state.ident;
+                let state = Ident::new("__state", proc_macro2::Span::mixed_site());
+                (quote! { #state @ Self::#ident { .. } }, quote! { #state })
+            }
+        };
 
-        // let input_ = input_value.match_on();
-        // let final_state_ = final_state.match_on();
-        let output_ = output
-            .as_ref()
+        let produced: Vec<&Final> = match final_state.variant_ref() {
+            // Exit and entry actions are only performed when the state is
+            // left, which is not the case for `_` transitions.
+            None => transition_outputs.iter().collect::<Vec<_>>(),
+            Some(next_state) => {
+                // Superstates containing both states are not left.
+                let from = hierarchy.ancestors(&initial_state.ident);
+                let to = hierarchy.ancestors(&next_state.ident);
+                let exited = std::iter::once(&initial_state.ident)
+                    .chain(from.iter().copied().filter(|x| !to.contains(x)));
+                let entered = to
+                    .iter()
+                    .rev()
+                    .copied()
+                    .filter(|x| !from.contains(x))
+                    .chain(std::iter::once(&next_state.ident));
+                exited
+                    .flat_map(|x| actions_of(&exit_actions, x))
+                    .chain(transition_outputs)
+                    .chain(entered.flat_map(|x| actions_of(&enter_actions, x)))
+                    .collect()
+            }
+        };
+
+        let source = initial_state.ident.to_string();
+        let target = final_state
+            .variant_ref()
+            .map_or(source.clone(), |x| x.ident.to_string());
+        let input = input_value.ident.to_string();
+        let output_names = produced.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        let guarded = !guard.is_empty();
+        definition.push_str(&format!(
+            "{source} {input} {target} [{}] {guarded}\n",
+            output_names.join(", ")
+        ));
+        transition_infos.push(quote! {
+            ::rust_fsm::TransitionInfo {
+                source: #source,
+                input: #input,
+                target: #target,
+                outputs: &[#(#output_names),*],
+                guarded: #guarded,
+            }
+        });
+
+        let produced = produced
+            .into_iter()
             .map(|x| {
-                #[cfg(feature = "diagram")]
-                mermaid_diagram.push_str(&format!(" [\"{x}\"]"));
                 let output = x.reduce().unwrap();
-                quote! { ::core::option::Option::Some(Self::Output::#output) }
+                quote! { Self::Output::#output }
             })
-            .unwrap_or(quote! { ::core::option::Option::None });
-        // let x = format!("{}, {} {} => {}", initial_, input_, guard, output_);
-        transition_cases.push(quote! {
-            (Self::#initial_, Self::Input::#input_) #guard => {
-                ::core::result::Result::Ok((Self::#final_, #output_))
-            }
-        });
+            .collect::<Vec<_>>();
+        max_outputs = max_outputs.max(produced.len());
+        transition_cases.push((
+            quote! { (#pattern, Self::Input::#input_) #guard },
+            final_,
+            produced,
+        ));
+    }
 
-        #[cfg(feature = "diagram")]
-        mermaid_diagram.push('\n');
+    outputs.extend(
+        enter_actions
+            .iter()
+            .chain(&exit_actions)
+            .filter_map(|(_, action)| action.clone().variant()),
+    );
 
-        states.push(initial_state.clone());
-        states.extend(final_state.clone().variant());
-        inputs.push(input_value.clone());
-        if let Some(output) = output {
-            outputs.push(output.clone().variant().unwrap());
+    let mut diagnostics = shadowed;
+    diagnostics.extend(analysis::analyze(
+        states.iter().map(|x| &x.ident),
+        inputs.iter().map(|x| &x.ident),
+        initial_state
+            .as_ref()
+            .and_then(Final::variant_ref)
+            .map(|x| &x.ident),
+        &final_states,
+        &edges,
+    ));
+    if options.strict {
+        if let Some(error) = combine_errors(diagnostics.iter().cloned()) {
+            return error.into_compile_error();
         }
     }
+    let warnings = diagnostics.iter().map(analysis::to_warning);
 
-    #[cfg(feature = "diagram")]
-    mermaid_diagram.push_str("///```");
-    #[cfg(feature = "diagram")]
-    let mermaid_diagram: proc_macro2::TokenStream = mermaid_diagram
-        .replace("::", "#58;#58;")
-        .replace('(', "#40;")
-        .replace(')', "#41;")
-        .replace('[', "#91;")
-        .replace(']', "#93;")
-        .replace('|', "#124;")
-        .replace("Default", "def")
-        .parse()
-        .unwrap();
+    if options.total {
+        if let Some(error) = combine_errors(analysis::find_unhandled(
+            states.iter().map(|x| &x.ident),
+            inputs.iter().map(|x| &x.ident),
+            &final_states,
+            &edges,
+        )) {
+            return error.into_compile_error();
+        }
+    }
+
+    // A single output is returned as an `Option`, more than that require a
+    // fixed-size collection.
+    let outputs_of = |produced: &[proc_macro2::TokenStream]| {
+        if max_outputs <= 1 {
+            produced.first().map_or(
+                quote! { ::core::option::Option::None },
+                |x| quote! { ::core::option::Option::Some(#x) },
+            )
+        } else {
+            let padding =
+                (produced.len()..max_outputs).map(|_| quote! { ::core::option::Option::None });
+            quote! {
+                ::rust_fsm::Outputs::from([
+                    #(::core::option::Option::Some(#produced),)*
+                    #(#padding),*
+                ])
+            }
+        }
+    };
+    let transition_cases = transition_cases
+        .into_iter()
+        .map(|(pattern, final_, produced)| (pattern, final_, outputs_of(&produced)))
+        .collect::<Vec<_>>();
+
+    // Total machines get an infallible transition function, in which final
+    // states ignore any input.
+    let total_impl = options.total.then(|| {
+        let cases = transition_cases
+            .iter()
+            .map(|(pattern, final_, produced)| quote! { #pattern => (#final_, #produced), });
+        let no_outputs = outputs_of(&[]);
+        let final_case = (!final_states.is_empty()).then(|| {
+            quote! {
+                (state @ (#(Self::#final_states { .. })|*), _) => (state, #no_outputs),
+            }
+        });
+        quote! {
+            fn transition_total(self, input: Self::Input<'_>) -> (Self, Self::Outputs<'_>) {
+                match (self, input) {
+                    #(#cases)*
+                    #final_case
+                }
+            }
+        }
+    });
+    let transition_cases = transition_cases.iter().map(|(pattern, final_, produced)| {
+        quote! {
+            #pattern => {
+                ::core::result::Result::Ok((#final_, #produced))
+            }
+        }
+    });
+
+    // The types of the data carried by the states, shown in the diagrams.
+    let payloads = states
+        .iter()
+        .filter(|x| x.field.is_some())
+        .filter_map(|x| Some((x.ident.clone(), variant::find_type(x, &states)?)))
+        .fold(
+            Vec::<(Ident, String)>::new(),
+            |mut payloads, (state, ty)| {
+                if !payloads.iter().any(|(x, _)| x == &state) {
+                    payloads.push((state, ty.to_token_stream().to_string()));
+                }
+                payloads
+            },
+        );
+    let graph = graph::Graph {
+        name: state_name.ident(),
+        states: &known_states,
+        initial_state: initial_state
+            .as_ref()
+            .and_then(Final::variant_ref)
+            .map(|x| &x.ident),
+        final_states: &final_states,
+        hierarchy: &hierarchy,
+        enter_actions: &enter_actions,
+        exit_actions: &exit_actions,
+        edges: &graph_edges,
+        payloads: &payloads,
+    };
+    let mermaid_diagram = graph.to_mermaid();
+    #[cfg(feature = "dot")]
+    let dot = graph.to_dot();
+    #[cfg(feature = "plantuml")]
+    let plantuml = graph.to_plantuml();
+    #[cfg(feature = "scxml")]
+    let scxml = graph.to_scxml();
+    // With `#[fsm(serde)]` the generated types are serializable and tagged
+    // with the names of the variants.
+    #[cfg(feature = "serde")]
+    let (serde_derive, serde_rename) =
+        (options.serde.then(serde::derive), |idents: &[&Ident]| {
+            idents
+                .iter()
+                .map(|x| options.serde.then(|| serde::rename(x)))
+                .collect::<Vec<_>>()
+        });
+    #[cfg(not(feature = "serde"))]
+    let (serde_derive, serde_rename) = (quote!(), |idents: &[&Ident]| vec![quote!(); idents.len()]);
     let input_generics = input_name.g();
+    let input_idents = variant::idents(&inputs);
+    let input_renames = serde_rename(&input_idents);
     let input_impl = variant::tokenize(&inputs, |x| {
         let attrs = attrs_to_token_stream(input_attrs);
         input_name.tokenize(|f| {
             quote! {
+                #serde_derive
                 #attrs
                 #input_visibility enum #f #input_generics {
-                    #(#x),*
+                    #(#input_renames #x),*
                 }
 
                 impl #f #input_generics {
                     #input_visibility fn name(&self) -> &'static str {
-                        match self { #(Self::#x => stringify!(#x)),* }
+                        match *self { #(Self::#input_idents { .. } => stringify!(#input_idents)),* }
                     }
                 }
             }
         })
     });
     let input_name = input_name.path();
+    let state_idents = variant::idents(&states);
+    let query_cases = query_cases
+        .into_iter()
+        .map(|(state, input, guard)| quote! { (#state, #input_name::#input) #guard => true, })
+        .collect::<Vec<_>>();
+    let can_consume_doc = if options.guards_by_ref {
+        "Whether there is a transition from this state on the input. The guards are \
+         evaluated on references to the data."
+    } else {
+        "Whether there is a transition from this state on the input, not taking the \
+         guards into account."
+    };
+    let accepted_inputs = state_idents
+        .iter()
+        .map(|state| {
+            let names = input_idents
+                .iter()
+                .filter(|input| {
+                    edges
+                        .iter()
+                        .any(|edge| &edge.from == *state && &edge.input == **input)
+                })
+                .map(ToString::to_string);
+            quote! { &[#(#names),*] }
+        })
+        .collect::<Vec<_>>();
+    let initial_fn = initial_state.as_ref().map(|initial_state| {
+        let initial_state = initial_state.reduce();
+        quote! {
+            /// The state this machine starts in.
+            #state_visibility fn initial() -> Self {
+                Self::#initial_state
+            }
+        }
+    });
+    let is_final = if final_states.is_empty() {
+        quote! { false }
+    } else {
+        quote! { matches!(*self, #(Self::#final_states { .. })|*) }
+    };
+    let parents = state_idents
+        .iter()
+        .map(|state| {
+            hierarchy
+                .parent(state)
+                .map_or(quote! { ::core::option::Option::None }, |parent| {
+                    let parent = parent.to_string();
+                    quote! { ::core::option::Option::Some(#parent) }
+                })
+        })
+        .collect::<Vec<_>>();
+    let state_renames = serde_rename(&state_idents);
     let state_impl = variant::tokenize(&states, |x| {
         let attrs = attrs_to_token_stream(state_attrs.clone());
         state_name.tokenize(|f| {
             quote! {
+                #serde_derive
                 #attrs
                 #state_visibility enum #f  {
-                    #(#x),*
+                    #(#state_renames #x),*
                 }
 
                 impl #f {
                     #state_visibility fn name(&self) -> &'static str {
-                        match self { #(Self::#x => stringify!(#x)),* }
+                        match *self { #(Self::#state_idents { .. } => stringify!(#state_idents)),* }
+                    }
+
+                    /// The name of the superstate containing this state, if any.
+                    #state_visibility fn parent(&self) -> ::core::option::Option<&'static str> {
+                        match *self { #(Self::#state_idents { .. } => #parents),* }
                     }
+
+                    /// Whether this is a final (accepting) state.
+                    #state_visibility fn is_final(&self) -> bool {
+                        #is_final
+                    }
+
+                    #[doc = #can_consume_doc]
+                    #[allow(unused_variables)]
+                    #state_visibility fn can_consume(
+                        &self,
+                        input: &<Self as ::rust_fsm::StateMachine>::Input<'_>,
+                    ) -> bool {
+                        match (self, input) {
+                            #(#query_cases)*
+                            _ => false,
+                        }
+                    }
+
+                    /// The names of the inputs this state has transitions on, not taking
+                    /// the guards into account.
+                    #state_visibility fn accepted_input_names(&self) -> &'static [&'static str] {
+                        match *self { #(Self::#state_idents { .. } => #accepted_inputs),* }
+                    }
+
+                    #initial_fn
                 }
             }
         })
     });
     let state_name = state_name.path();
+    #[cfg(feature = "dot")]
+    let dot_impl = quote! {
+        impl ::rust_fsm::DotDiagram for #state_name {
+            const DOT: &'static str = #dot;
+        }
+    };
+    #[cfg(not(feature = "dot"))]
+    let dot_impl = quote!();
+    #[cfg(feature = "plantuml")]
+    let plantuml_impl = quote! {
+        impl ::rust_fsm::PlantUmlDiagram for #state_name {
+            const PLANTUML: &'static str = #plantuml;
+        }
+    };
+    #[cfg(not(feature = "plantuml"))]
+    let plantuml_impl = quote!();
+    #[cfg(feature = "scxml")]
+    let scxml_impl = quote! {
+        impl ::rust_fsm::ScxmlDocument for #state_name {
+            const SCXML: &'static str = #scxml;
+        }
+    };
+    #[cfg(not(feature = "scxml"))]
+    let scxml_impl = quote!();
+    let initial_state_name = initial_state.as_ref().and_then(Final::variant_ref).map_or(
+        quote! { ::core::option::Option::None },
+        |x| {
+            let name = x.ident.to_string();
+            quote! { ::core::option::Option::Some(#name) }
+        },
+    );
+    let total_impl = total_impl.map(|transition_total| {
+        quote! {
+            impl ::rust_fsm::TotalStateMachine for #state_name {
+                #transition_total
+            }
+        }
+    });
+    let default_impl = initial_state.map(|initial_state| {
+        let initial_state = initial_state.reduce();
+        quote! {
+            impl ::core::default::Default for #state_name {
+                fn default() -> Self {
+                    Self::#initial_state
+                }
+            }
+        }
+    });
     let output_generics = output_name.g();
+    let output_idents = variant::idents(&outputs);
+    let output_renames = serde_rename(&output_idents);
     let output_impl = variant::tokenize(&outputs, |outputs| {
         let attrs = attrs_to_token_stream(output_attrs);
         output_name.tokenize(|output_name| {
             quote! {
+                #serde_derive
                 #attrs
                 #output_visibility enum #output_name #output_generics {
-                    #(#outputs),*
+                    #(#output_renames #outputs),*
                 }
 
                 impl #output_name #output_generics {
                     #output_visibility fn name(&self) -> &'static str {
-                        match self { #(Self::#outputs => stringify!(#outputs)),* }
+                        match *self { #(Self::#output_idents { .. } => stringify!(#output_idents)),* }
                     }
                 }
             }
@@ -220,28 +807,72 @@ pub fn state_machine(tokens: TokenStream) -> TokenStream {
     });
 
     let output_name = output_name.path();
+    let outputs_type = if max_outputs <= 1 {
+        quote! { ::core::option::Option<Self::Output<'i>> }
+    } else {
+        quote! { ::rust_fsm::Outputs<Self::Output<'i>, #max_outputs> }
+    };
+
+    let definition_hash = {
+        let states = state_idents.iter().map(ToString::to_string);
+        definition_hash(&format!(
+            "{}\n{definition}",
+            states.collect::<Vec<_>>().join(" ")
+        ))
+    };
+    let meta_impl = quote! {
+        impl ::rust_fsm::StateMachineMeta for #state_name {
+            const STATES: &'static [&'static str] = &[#(stringify!(#state_idents)),*];
+            const INPUTS: &'static [&'static str] = &[#(stringify!(#input_idents)),*];
+            const OUTPUTS: &'static [&'static str] = &[#(stringify!(#output_idents)),*];
+            const INITIAL_STATE: ::core::option::Option<&'static str> = #initial_state_name;
+            const FINAL_STATES: &'static [&'static str] = &[#(stringify!(#final_states)),*];
+            const TRANSITIONS: &'static [::rust_fsm::TransitionInfo] = &[#(#transition_infos),*];
+            const DEFINITION_HASH: u64 = #definition_hash;
+
+            fn state_name(&self) -> &'static str {
+                match *self { #(Self::#state_idents { .. } => stringify!(#state_idents)),* }
+            }
+
+            fn input_name(input: &Self::Input<'_>) -> &'static str {
+                match *input { #(#input_name::#input_idents { .. } => stringify!(#input_idents)),* }
+            }
+
+            fn output_name(output: &Self::Output<'_>) -> &'static str {
+                match *output { #(#output_name::#output_idents { .. } => stringify!(#output_idents)),* }
+            }
+        }
+    };
 
     #[cfg(feature = "diagram")]
-    let diagram = quote! {
-        #[cfg_attr(doc, ::rust_fsm::aquamarine)]
-        #mermaid_diagram
+    let diagram = {
+        let lines = mermaid_diagram.lines();
+        quote! {
+            #[cfg_attr(doc, ::rust_fsm::aquamarine)]
+            #[doc = "```mermaid"]
+            #(#[doc = #lines])*
+            #[doc = "```"]
+        }
     };
 
     #[cfg(not(feature = "diagram"))]
     let diagram = quote!();
     let output = quote! {
+        #(#warnings)*
         #input_impl
         #doc
         #diagram
         #state_impl
+        #default_impl
         #output_impl
 
         impl ::rust_fsm::StateMachine for #state_name {
             type Input<'i> = #input_name #input_generics;
             type Output<'i> = #output_name #output_generics;
+            type Outputs<'i> = #outputs_type;
 
             fn transition(self, input: Self::Input<'_>) -> ::core::result::Result<
-                (Self, ::core::option::Option<Self::Output<'_>>),
+                (Self, Self::Outputs<'_>),
                 ::rust_fsm::TransitionImpossibleError<Self, Self::Input<'_>>
             > {
                 match (self, input) {
@@ -251,7 +882,17 @@ pub fn state_machine(tokens: TokenStream) -> TokenStream {
             }
         }
 
+        #total_impl
+
+        #meta_impl
+
+        impl ::rust_fsm::MermaidDiagram for #state_name {
+            const MERMAID: &'static str = #mermaid_diagram;
+        }
+        #dot_impl
+        #plantuml_impl
+        #scxml_impl
     };
 
-    output.into()
+    output
 }
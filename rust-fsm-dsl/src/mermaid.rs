@@ -0,0 +1,95 @@
+use std::fmt::Write;
+
+use syn::Ident;
+
+use crate::graph::Graph;
+
+/// Escapes the characters that have a meaning in the Mermaid syntax with
+/// their entity codes, e.g. `:` becomes `#58;`. Identifiers are never changed.
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if "#;:()[]{}<>|\"%-".contains(c) || c.is_control() {
+                format!("#{};", u32::from(c))
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+impl Graph<'_> {
+    /// Writes the `state` superstate as a Mermaid composite state.
+    fn write_mermaid_superstate(&self, diagram: &mut String, state: &Ident, depth: usize) {
+        let indent = "    ".repeat(depth);
+        writeln!(diagram, "{indent}state {state} {{").unwrap();
+        writeln!(
+            diagram,
+            "{indent}    [*] --> {}",
+            self.hierarchy.children(state).next().unwrap()
+        )
+        .unwrap();
+        for child in self.hierarchy.children(state) {
+            if self.hierarchy.is_superstate(child) {
+                self.write_mermaid_superstate(diagram, child, depth + 1);
+            } else {
+                writeln!(diagram, "{indent}    {child}").unwrap();
+            }
+        }
+        writeln!(diagram, "{indent}}}").unwrap();
+    }
+
+    /// Renders the diagram in the Mermaid format. The transitions are
+    /// labelled as `input [guard] / outputs`, and the types of the data
+    /// carried by the states are shown in notes.
+    pub fn to_mermaid(&self) -> String {
+        let mut diagram = "stateDiagram-v2\n".to_string();
+        if let Some(initial_state) = self.initial_state {
+            writeln!(diagram, "    [*] --> {initial_state}").unwrap();
+        }
+        for superstate in self.hierarchy.roots() {
+            self.write_mermaid_superstate(&mut diagram, superstate, 1);
+        }
+        for edge in self.edges {
+            let mut label = edge.pattern.clone();
+            if let Some(guard) = &edge.guard {
+                write!(label, " [{guard}]").unwrap();
+            }
+            if !edge.outputs.is_empty() {
+                write!(label, " / {}", edge.outputs.join(", ")).unwrap();
+            }
+            writeln!(
+                diagram,
+                "    {} --> {}: {}",
+                edge.from,
+                edge.to.as_ref().unwrap_or(&edge.from),
+                escape(&label)
+            )
+            .unwrap();
+        }
+        for (state, action) in self.enter_actions {
+            writeln!(
+                diagram,
+                "    {state}: enter / {}",
+                escape(&action.to_string())
+            )
+            .unwrap();
+        }
+        for (state, action) in self.exit_actions {
+            writeln!(
+                diagram,
+                "    {state}: exit / {}",
+                escape(&action.to_string())
+            )
+            .unwrap();
+        }
+        for (state, ty) in self.payloads {
+            writeln!(diagram, "    note left of {state}: {}", escape(ty)).unwrap();
+        }
+        for final_state in self.final_states {
+            writeln!(diagram, "    {final_state} --> [*]").unwrap();
+        }
+        diagram.truncate(diagram.trim_end().len());
+        diagram
+    }
+}
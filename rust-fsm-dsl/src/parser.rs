@@ -1,4 +1,4 @@
-use crate::variant::Final;
+use crate::{hierarchy::Hierarchy, variant::Final};
 
 use super::variant::Variant;
 use proc_macro2::TokenStream;
@@ -7,22 +7,50 @@ use syn::{
     token::Bracket,
     *,
 };
-/// The output of a state transition
-pub struct Output(Option<Final>);
+mod kw {
+    syn::custom_keyword!(enter);
+    syn::custom_keyword!(except);
+    syn::custom_keyword!(exit);
+    syn::custom_keyword!(state);
+}
+
+/// The outputs of a state transition: `[Output1, Output2]`
+pub struct Output(Vec<Final>);
 
 impl Parse for Output {
     fn parse(input: ParseStream) -> Result<Self> {
         if input.lookahead1().peek(Bracket) {
             let output_content;
             bracketed!(output_content in input);
-            Ok(Self(Some(output_content.parse()?)))
+            output_content
+                .parse_terminated(Final::parse, Token![,])?
+                .into_iter()
+                .map(|output| match output.variant_ref() {
+                    Some(_) => Ok(output),
+                    None => Err(output_content.error("`_` cannot be used as an output")),
+                })
+                .collect::<Result<_>>()
+                .map(Self)
         } else {
-            Ok(Self(None))
+            Ok(Self(Vec::new()))
         }
     }
 }
 
-impl From<Output> for Option<Final> {
+/// Parses entry or exit actions of a state: `State [Output1, Output2]`.
+fn parse_actions(input: ParseStream) -> Result<Vec<(Ident, Final)>> {
+    let state: Ident = input.parse()?;
+    if !input.peek(Bracket) {
+        return Err(input.error("expected the outputs of the action in brackets"));
+    }
+    let Output(outputs) = input.parse()?;
+    Ok(outputs
+        .into_iter()
+        .map(|output| (state.clone(), output))
+        .collect())
+}
+
+impl From<Output> for Vec<Final> {
     fn from(output: Output) -> Self {
         output.0
     }
@@ -33,7 +61,7 @@ impl From<Output> for Option<Final> {
 pub struct TransitionEntry {
     pub input_value: Variant,
     pub final_state: Final,
-    pub output: Option<Final>,
+    pub outputs: Vec<Final>,
 }
 
 impl Parse for TransitionEntry {
@@ -41,24 +69,51 @@ impl Parse for TransitionEntry {
         let input_value = input.parse()?;
         input.parse::<Token![=>]>()?;
         let final_state = input.parse()?;
-        let output = input.parse::<Output>()?.into();
+        let outputs = input.parse::<Output>()?.into();
         Ok(Self {
             input_value,
             final_state,
-            output,
+            outputs,
         })
     }
 }
 
+/// The state a transition starts from.
+pub enum Source {
+    /// A state or a superstate.
+    State(Box<Variant>),
+    /// Any state except the listed ones: `_` or `_ except State1 | State2`.
+    Any(Vec<Ident>),
+}
+
+impl Parse for Source {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if !input.peek(Token![_]) {
+            return input.parse().map(|x| Self::State(Box::new(x)));
+        }
+        input.parse::<Token![_]>()?;
+        let mut except = Vec::new();
+        if input.peek(kw::except) {
+            input.parse::<kw::except>()?;
+            except.push(input.parse()?);
+            while input.peek(Token![|]) {
+                input.parse::<Token![|]>()?;
+                except.push(input.parse()?);
+            }
+        }
+        Ok(Self::Any(except))
+    }
+}
+
 /// Parses the transition in any of the possible formats.
 pub struct TransitionDef {
-    pub initial_state: Variant,
+    pub source: Source,
     pub transitions: Vec<TransitionEntry>,
 }
 
 impl Parse for TransitionDef {
     fn parse(input: ParseStream) -> Result<Self> {
-        let initial_state: Variant = input.parse()?;
+        let source: Source = input.parse()?;
         input.parse::<Token![=>]>()?;
         // Parse the transition in the simple format
         // InitialState => Input => ResultState
@@ -66,12 +121,12 @@ impl Parse for TransitionDef {
             let input_value = input.parse()?;
             input.parse::<Token![=>]>()?;
             let final_state = input.parse()?;
-            let output = input.parse::<Output>()?.into();
+            let outputs = input.parse::<Output>()?.into();
 
             vec![TransitionEntry {
                 input_value,
                 final_state,
-                output,
+                outputs,
             }]
         } else {
             // Parse the transition in the compact format
@@ -87,15 +142,18 @@ impl Parse for TransitionDef {
                 .into_iter()
                 .collect();
             if entries.is_empty() {
-                return Err(Error::new_spanned(
-                    initial_state.ident,
-                    "No transitions provided for a compact representation",
-                ));
+                let message = "No transitions provided for a compact representation";
+                return Err(match &source {
+                    Source::State(initial_state) => {
+                        Error::new_spanned(&initial_state.ident, message)
+                    }
+                    Source::Any(_) => entries_content.error(message),
+                });
             }
             entries
         };
         Ok(Self {
-            initial_state,
+            source,
             transitions,
         })
     }
@@ -105,23 +163,82 @@ impl Parse for TransitionDef {
 ///
 /// ```rust,ignore
 /// state_machine! {
-///     CircuitBreaker(Closed)
+///     #[fsm(strict, total)]
+///     CircuitBreaker(Closed) => Result => Action
 ///
-///     Closed(Unsuccessful) => Open [SetupTimer],
-///     Open(TimerTriggered) => HalfOpen,
+///     Closed => Unsuccessful => Open [SetupTimer],
+///     Open => TimerTriggered => HalfOpen,
 ///     HalfOpen => {
 ///         Successful => Closed,
 ///         Unsuccessful => Open [SetupTimer]
-///     }
+///     },
+///     final Broken,
+///     enter Open [StartTimer, NotifyOps],
+///     exit Open [CancelTimer],
+///     state Closed { Idle, Busy },
+///     _ except Broken => Reset => Closed,
 /// }
 /// ```
 pub struct StateMachineDef {
     pub doc: Vec<Attribute>,
+    pub options: Options,
 
     pub state_name: (Vec<Attribute>, Visibility, ImplementationRequired),
+    /// The state the machine starts in, e.g. `Closed` in `CircuitBreaker(Closed)`.
+    pub initial_state: Option<Final>,
     pub input_name: (Vec<Attribute>, Visibility, ImplementationRequired),
     pub output_name: (Vec<Attribute>, Visibility, ImplementationRequired),
     pub transitions: Vec<TransitionDef>,
+    pub final_states: Vec<Ident>,
+    /// Outputs produced when entering a state, declared as `enter State [Outputs]`.
+    pub enter_actions: Vec<(Ident, Final)>,
+    /// Outputs produced when leaving a state, declared as `exit State [Outputs]`.
+    pub exit_actions: Vec<(Ident, Final)>,
+    /// Superstates declared as `state Parent { Child1, Child2 }`.
+    pub hierarchy: Hierarchy,
+}
+
+/// Options of the macro declared as `#[fsm(option1, option2)]` attributes.
+#[derive(Default)]
+pub struct Options {
+    /// Report the problems found by analyzing the machine as errors instead of
+    /// warnings: `#[fsm(strict)]`.
+    pub strict: bool,
+    /// Require a transition for every combination of a state and an input:
+    /// `#[fsm(total)]`.
+    pub total: bool,
+    /// Evaluate the guards in the `can_consume()` query on references to the
+    /// data: `#[fsm(guards_by_ref)]`.
+    pub guards_by_ref: bool,
+    /// Derive `Serialize` and `Deserialize` for the generated types:
+    /// `#[fsm(serde)]`. Requires the `serde` feature.
+    pub serde: bool,
+}
+
+impl Options {
+    fn parse_attribute(&mut self, attribute: &Attribute) -> Result<()> {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("strict") {
+                self.strict = true;
+                Ok(())
+            } else if meta.path.is_ident("total") {
+                self.total = true;
+                Ok(())
+            } else if meta.path.is_ident("guards_by_ref") {
+                self.guards_by_ref = true;
+                Ok(())
+            } else if meta.path.is_ident("serde") {
+                if cfg!(feature = "serde") {
+                    self.serde = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("the `serde` option requires the `serde` feature of rust-fsm"))
+                }
+            } else {
+                Err(meta.error("unknown rust-fsm option"))
+            }
+        })
+    }
 }
 
 pub enum ImplementationRequired {
@@ -142,6 +259,13 @@ impl ImplementationRequired {
             ImplementationRequired::No(_) => TokenStream::default(),
         }
     }
+    /// The name of the type without the path.
+    pub fn ident(&self) -> &Ident {
+        match self {
+            ImplementationRequired::Yes(ident, _) => ident,
+            ImplementationRequired::No(path) => &path.segments.last().unwrap().ident,
+        }
+    }
     pub fn path(self) -> Path {
         match self {
             ImplementationRequired::Yes(ident, _) => ident.into(),
@@ -153,6 +277,7 @@ impl ImplementationRequired {
 impl Parse for StateMachineDef {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut doc = Vec::new();
+        let mut options = Vec::new();
         let mut i = || {
             let attributes = Attribute::parse_outer(input)?
                 .into_iter()
@@ -160,6 +285,9 @@ impl Parse for StateMachineDef {
                     if attribute.path().is_ident("doc") {
                         doc.push(attribute);
                         None
+                    } else if attribute.path().is_ident("fsm") {
+                        options.push(attribute);
+                        None
                     } else {
                         Some(attribute)
                     }
@@ -167,39 +295,81 @@ impl Parse for StateMachineDef {
                 .collect();
             let visibility = input.parse()?;
 
-            input
-                .peek(Token![::])
-                .then(|| {
-                    input.parse::<Path>().map(|mut x| {
-                        x.leading_colon = None;
-                        ImplementationRequired::No(x)
-                    })
+            if input.peek(Token![::]) {
+                input.parse::<Path>().map(|mut x| {
+                    x.leading_colon = None;
+                    ImplementationRequired::No(x)
                 })
-                .unwrap_or_else(|| {
-                    let t = input.parse::<Ident>()?;
-                    let g = input.parse::<Generics>()?;
-                    Ok(ImplementationRequired::Yes(t, g))
-                })
-                .map(|x| (attributes, visibility, x))
+            } else {
+                let t = input.parse::<Ident>()?;
+                let g = input.parse::<Generics>()?;
+                Ok(ImplementationRequired::Yes(t, g))
+            }
+            .map(|x| (attributes, visibility, x))
         };
 
         let state_name = i()?;
+        let initial_state = if input.peek(token::Paren) {
+            let initial_content;
+            parenthesized!(initial_content in input);
+            let initial_state: Final = initial_content.parse()?;
+            if initial_state.variant_ref().is_none() {
+                return Err(initial_content.error("the initial state must be a concrete state"));
+            }
+            Some(initial_state)
+        } else {
+            None
+        };
         input.parse::<Token![=>]>()?;
         let input_name = i()?;
         input.parse::<Token![=>]>()?;
         let output_name = i()?;
+        let options = options.iter().try_fold(Options::default(), |mut x, y| {
+            x.parse_attribute(y)?;
+            Ok::<_, Error>(x)
+        })?;
 
-        let transitions = input
-            .parse_terminated(TransitionDef::parse, Token![,])?
-            .into_iter()
-            .collect();
+        let mut transitions = Vec::new();
+        let mut final_states = Vec::new();
+        let mut enter_actions = Vec::new();
+        let mut exit_actions = Vec::new();
+        let mut hierarchy = Hierarchy::default();
+        while !input.is_empty() {
+            if input.peek(Token![final]) {
+                // A final (accepting) state declared as `final State`
+                input.parse::<Token![final]>()?;
+                final_states.push(input.parse()?);
+            } else if input.peek(kw::enter) && input.peek2(Ident) {
+                input.parse::<kw::enter>()?;
+                enter_actions.extend(parse_actions(input)?);
+            } else if input.peek(kw::exit) && input.peek2(Ident) {
+                input.parse::<kw::exit>()?;
+                exit_actions.extend(parse_actions(input)?);
+            } else if input.peek(kw::state) && input.peek2(Ident) {
+                input.parse::<kw::state>()?;
+                let parent = input.parse()?;
+                hierarchy.parse_children(input, &parent)?;
+            } else {
+                transitions.push(input.parse()?);
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
 
         Ok(Self {
             doc,
+            options,
             state_name,
+            initial_state,
             input_name,
             output_name,
             transitions,
+            final_states,
+            enter_actions,
+            exit_actions,
+            hierarchy,
         })
     }
 }
@@ -0,0 +1,59 @@
+use std::fmt::Write;
+
+use syn::Ident;
+
+use crate::graph::Graph;
+
+impl Graph<'_> {
+    fn write_plantuml_state(&self, uml: &mut String, state: &Ident, indent: &str) {
+        if self.hierarchy.is_superstate(state) {
+            writeln!(uml, "{indent}state {state} {{").unwrap();
+            writeln!(
+                uml,
+                "{indent}    [*] --> {}",
+                self.hierarchy.children(state).next().unwrap()
+            )
+            .unwrap();
+            for child in self.hierarchy.children(state) {
+                self.write_plantuml_state(uml, child, &format!("{indent}    "));
+            }
+            writeln!(uml, "{indent}}}").unwrap();
+        } else {
+            writeln!(uml, "{indent}state {state}").unwrap();
+        }
+        for action in self.enter_actions_of(state) {
+            writeln!(uml, "{indent}{state} : entry / {action}").unwrap();
+        }
+        for action in self.exit_actions_of(state) {
+            writeln!(uml, "{indent}{state} : exit / {action}").unwrap();
+        }
+    }
+
+    /// Renders the diagram in the PlantUML format. The transitions are
+    /// labelled as `input [guard] / outputs`.
+    pub fn to_plantuml(&self) -> String {
+        let mut uml = "@startuml\nhide empty description\n".to_string();
+        if let Some(initial_state) = self.initial_state {
+            writeln!(uml, "[*] --> {initial_state}").unwrap();
+        }
+        for state in self.top_level_states() {
+            self.write_plantuml_state(&mut uml, state, "");
+        }
+        for edge in self.edges {
+            let to = edge.to.as_ref().unwrap_or(&edge.from);
+            let mut label = edge.pattern.clone();
+            if let Some(guard) = &edge.guard {
+                write!(label, " [{guard}]").unwrap();
+            }
+            if !edge.outputs.is_empty() {
+                write!(label, " / {}", edge.outputs.join(", ")).unwrap();
+            }
+            writeln!(uml, "{} --> {to} : {label}", edge.from).unwrap();
+        }
+        for final_state in self.final_states {
+            writeln!(uml, "{final_state} --> [*]").unwrap();
+        }
+        uml.push_str("@enduml");
+        uml
+    }
+}
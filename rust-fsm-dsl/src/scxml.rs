@@ -0,0 +1,103 @@
+use std::fmt::Write;
+
+use syn::Ident;
+
+use crate::graph::Graph;
+
+/// Escapes a string to be used as an XML attribute value.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes the outputs as events sent to the parent session.
+fn write_outputs(xml: &mut String, outputs: &[String], indent: &str) {
+    for output in outputs {
+        writeln!(
+            xml,
+            "{indent}<send event=\"{}\" target=\"#_parent\"/>",
+            escape(output)
+        )
+        .unwrap();
+    }
+}
+
+impl Graph<'_> {
+    fn write_scxml_state(&self, xml: &mut String, state: &Ident, indent: &str) {
+        let inner = format!("{indent}    ");
+        let mut content = String::new();
+        let enter_actions = self.enter_actions_of(state);
+        if !enter_actions.is_empty() {
+            writeln!(content, "{inner}<onentry>").unwrap();
+            write_outputs(&mut content, &enter_actions, &format!("{inner}    "));
+            writeln!(content, "{inner}</onentry>").unwrap();
+        }
+        let exit_actions = self.exit_actions_of(state);
+        if !exit_actions.is_empty() {
+            writeln!(content, "{inner}<onexit>").unwrap();
+            write_outputs(&mut content, &exit_actions, &format!("{inner}    "));
+            writeln!(content, "{inner}</onexit>").unwrap();
+        }
+        for edge in self.edges.iter().filter(|edge| &edge.from == state) {
+            write!(content, "{inner}<transition event=\"{}\"", edge.input).unwrap();
+            if let Some(guard) = &edge.guard {
+                write!(content, " cond=\"{}\"", escape(guard)).unwrap();
+            }
+            // Transitions without a target keep the current state without
+            // leaving it, just like `_` transitions.
+            if let Some(to) = &edge.to {
+                write!(content, " target=\"{to}\"").unwrap();
+            }
+            if edge.outputs.is_empty() {
+                content.push_str("/>\n");
+            } else {
+                content.push_str(">\n");
+                write_outputs(&mut content, &edge.outputs, &format!("{inner}    "));
+                writeln!(content, "{inner}</transition>").unwrap();
+            }
+        }
+        for child in self.hierarchy.children(state) {
+            self.write_scxml_state(&mut content, child, &inner);
+        }
+
+        let tag = if self.final_states.contains(state) {
+            "final"
+        } else {
+            "state"
+        };
+        write!(xml, "{indent}<{tag} id=\"{state}\"").unwrap();
+        if let Some(child) = self.hierarchy.children(state).next() {
+            write!(xml, " initial=\"{child}\"").unwrap();
+        }
+        if content.is_empty() {
+            xml.push_str("/>\n");
+        } else {
+            writeln!(xml, ">\n{content}{indent}</{tag}>").unwrap();
+        }
+    }
+
+    /// Renders the machine as a W3C SCXML document. Guards become the
+    /// conditions of the transitions and outputs are sent to the parent
+    /// session as events.
+    pub fn to_scxml(&self) -> String {
+        let mut xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n".to_string();
+        write!(
+            xml,
+            "<scxml xmlns=\"http://www.w3.org/2005/07/scxml\" version=\"1.0\" name=\"{}\"",
+            self.name
+        )
+        .unwrap();
+        if let Some(initial_state) = self.initial_state {
+            write!(xml, " initial=\"{initial_state}\"").unwrap();
+        }
+        xml.push_str(">\n");
+        for state in self.top_level_states() {
+            self.write_scxml_state(&mut xml, state, "    ");
+        }
+        xml.push_str("</scxml>");
+        xml
+    }
+}
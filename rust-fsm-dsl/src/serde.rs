@@ -0,0 +1,18 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+/// The attributes deriving `Serialize` and `Deserialize` for a generated type.
+pub fn derive() -> TokenStream {
+    quote! {
+        #[derive(::rust_fsm::serde::Serialize, ::rust_fsm::serde::Deserialize)]
+        #[serde(crate = "::rust_fsm::serde")]
+    }
+}
+
+/// Tags the variant with its name, so the serialized form does not depend on
+/// the `rename_all` attribute of the type.
+pub fn rename(variant: &Ident) -> TokenStream {
+    let name = variant.to_string();
+    quote! { #[serde(rename = #name)] }
+}
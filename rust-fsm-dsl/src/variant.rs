@@ -20,6 +20,13 @@ pub fn find_type(of: &Variant, list: &[Variant]) -> Option<Type> {
         })
     })
 }
+/// Deduplicated variant names in the same order as produced by [`tokenize`].
+pub fn idents(inputs: &[Variant]) -> Vec<&Ident> {
+    BTreeSet::from_iter(inputs)
+        .into_iter()
+        .map(|x| &x.ident)
+        .collect()
+}
 pub fn tokenize(
     inputs: &[Variant],
     f: impl FnOnce(Vec<TokenStream>) -> TokenStream,
@@ -27,6 +34,11 @@ pub fn tokenize(
     let (Ok(x) | Err(x)) = BTreeSet::from_iter(inputs)
         .into_iter()
         .map(|x| {
+            // Prefer an occurrence carrying data, if there is one
+            let x = inputs
+                .iter()
+                .find(|y| y.ident == x.ident && y.field.is_some())
+                .unwrap_or(x);
             let i = &x.ident;
             x.field.as_ref().map_or(Ok(quote! { #i }), |_| {
                 let y = find_type(x, inputs);
@@ -115,6 +127,20 @@ impl Variant {
             self.ident.to_token_stream()
         }
     }
+    /// Whether the variant is matched regardless of the data it carries.
+    pub fn is_irrefutable(&self) -> bool {
+        self.field
+            .as_ref()
+            .is_none_or(|(_, pattern, guard)| guard.is_none() && irrefutable(pattern))
+    }
+    /// The pattern without the guard, reduced to the name of the variant if it
+    /// matches any data, so that e.g. `Kick(n)` and `Kick(_)` compare equal.
+    pub fn normalized_pattern(&self) -> String {
+        match &self.field {
+            Some((_, pattern, _)) if !irrefutable(pattern) => self.separate().0.to_string(),
+            _ => self.ident.to_string(),
+        }
+    }
     pub fn separate(&self) -> (proc_macro2::TokenStream, Option<proc_macro2::TokenStream>) {
         if let Self {
             ident,
@@ -135,6 +161,17 @@ impl Variant {
     }
 }
 
+/// Whether the pattern matches any value, binding it or not.
+fn irrefutable(pattern: &Pat) -> bool {
+    match pattern {
+        Pat::Wild(_) | Pat::Rest(_) => true,
+        Pat::Ident(PatIdent { subpat, .. }) => subpat.as_ref().is_none_or(|(_, x)| irrefutable(x)),
+        Pat::Tuple(PatTuple { elems, .. }) => elems.iter().all(irrefutable),
+        Pat::Paren(PatParen { pat, .. }) | Pat::Type(PatType { pat, .. }) => irrefutable(pat),
+        _ => false,
+    }
+}
+
 impl Display for Variant {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.ident)
@@ -204,6 +241,15 @@ impl Final {
     pub fn variant(self) -> Option<Variant> {
         self.0
     }
+    pub fn variant_ref(&self) -> Option<&Variant> {
+        self.0.as_ref()
+    }
+}
+
+impl From<Ident> for Final {
+    fn from(ident: Ident) -> Self {
+        Self(Some(Variant { ident, field: None }))
+    }
 }
 
 impl Display for Final {
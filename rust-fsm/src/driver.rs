@@ -0,0 +1,262 @@
+use core::{
+    fmt::{self, Debug},
+    future::poll_fn,
+    task::{Poll, Waker},
+};
+use std::{
+    error::Error,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+
+use crate::{StateMachine, TransitionImpossibleError};
+
+/// Runs a state machine asynchronously: consumes the inputs received from a
+/// [`Stream`] and sends the outputs to a [`Sink`], until the stream ends.
+/// Does not depend on any particular executor. Requires the `async` feature.
+///
+/// The current state can be observed with a [`Watch`] obtained from
+/// [`Driver::watch`] before running the machine.
+///
+/// ```rust
+/// use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
+/// use rust_fsm::*;
+///
+/// state_machine! {
+///     #[derive(Debug, Clone, PartialEq)]
+///     Door(Closed) => #[derive(Debug)] Action => #[derive(Debug, PartialEq)] Effect
+///
+///     Closed => Open => Opened [Creak],
+///     Opened => Close => Closed,
+/// }
+///
+/// let (mut inputs, input_stream) = mpsc::unbounded();
+/// let (output_sink, outputs) = mpsc::unbounded();
+/// let driver = Driver::new(Door::Closed, input_stream, output_sink);
+/// let watch = driver.watch();
+///
+/// block_on(async {
+///     inputs.send(Action::Open).await.unwrap();
+///     inputs.close_channel();
+///     assert_eq!(driver.run().await.unwrap(), Door::Opened);
+/// });
+/// assert_eq!(watch.get(), Door::Opened);
+/// assert_eq!(block_on(outputs.collect::<Vec<_>>()), [Effect::Creak]);
+/// ```
+pub struct Driver<M, I, O> {
+    machine: M,
+    inputs: I,
+    outputs: O,
+    shared: Publisher<M>,
+}
+
+impl<M: Clone, I, O> Driver<M, I, O> {
+    /// Drives the machine with the inputs received from `inputs`, sending the
+    /// outputs to `outputs`.
+    pub fn new(machine: M, inputs: I, outputs: O) -> Self {
+        let shared = Arc::new(Shared {
+            inner: Mutex::new(Inner {
+                state: machine.clone(),
+                version: 0,
+                closed: false,
+                wakers: Vec::new(),
+            }),
+        });
+        Self {
+            machine,
+            inputs,
+            outputs,
+            shared: Publisher(shared),
+        }
+    }
+
+    /// A handle to observe the state of the machine while it is running.
+    pub fn watch(&self) -> Watch<M> {
+        Watch {
+            shared: self.shared.0.clone(),
+            seen: self.shared.0.lock().version,
+        }
+    }
+
+    /// The current state of the machine.
+    pub fn state(&self) -> &M {
+        &self.machine
+    }
+
+    /// Consumes the inputs until the stream ends and returns the final state
+    /// of the machine. Stops at the first input the machine cannot consume or
+    /// when the outputs cannot be sent, returning the machine in the error.
+    #[allow(clippy::type_complexity)]
+    pub async fn run<'i>(self) -> Result<M, DriverError<M, M::Input<'i>, O::Error>>
+    where
+        M: StateMachine,
+        I: Stream<Item = M::Input<'i>> + Unpin,
+        O: Sink<M::Output<'i>> + Unpin,
+    {
+        let Self {
+            mut machine,
+            mut inputs,
+            mut outputs,
+            shared,
+        } = self;
+        while let Some(input) = inputs.next().await {
+            let produced;
+            (machine, produced) = machine
+                .transition(input)
+                .map_err(DriverError::TransitionImpossible)?;
+            shared.publish(&machine);
+            for output in produced {
+                if let Err(error) = outputs.feed(output).await {
+                    return Err(DriverError::Sink { machine, error });
+                }
+            }
+            if let Err(error) = outputs.flush().await {
+                return Err(DriverError::Sink { machine, error });
+            }
+        }
+        Ok(machine)
+    }
+}
+
+impl<M: Debug, I, O> Debug for Driver<M, I, O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Driver")
+            .field("machine", &self.machine)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The reason a [`Driver`] stopped before its inputs ended.
+#[derive(Debug)]
+pub enum DriverError<M, I, E> {
+    /// There is no transition from the state on the input.
+    TransitionImpossible(TransitionImpossibleError<M, I>),
+    /// The outputs could not be sent. The transition producing them has
+    /// already been performed.
+    Sink {
+        /// The machine after the transition.
+        machine: M,
+        error: E,
+    },
+}
+
+impl<M: Debug, I: Debug, E: fmt::Display> fmt::Display for DriverError<M, I, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TransitionImpossible(error) => write!(f, "{error}"),
+            Self::Sink { error, .. } => write!(f, "cannot send the outputs: {error}"),
+        }
+    }
+}
+
+impl<M, I, E> Error for DriverError<M, I, E>
+where
+    M: Debug + 'static,
+    I: Debug + 'static,
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::TransitionImpossible(error) => Some(error),
+            Self::Sink { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Observes the state of a machine run by a [`Driver`].
+#[derive(Debug)]
+pub struct Watch<M> {
+    shared: Arc<Shared<M>>,
+    /// The version of the state last seen by this handle.
+    seen: u64,
+}
+
+impl<M: Clone> Watch<M> {
+    /// The current state of the machine.
+    pub fn get(&self) -> M {
+        self.shared.lock().state.clone()
+    }
+
+    /// Waits until the state changes since it was last seen and returns the
+    /// new state. Returns `None` once the driver has stopped.
+    pub async fn changed(&mut self) -> Option<M> {
+        poll_fn(|cx| {
+            let mut inner = self.shared.lock();
+            if inner.version != self.seen {
+                self.seen = inner.version;
+                Poll::Ready(Some(inner.state.clone()))
+            } else if inner.closed {
+                Poll::Ready(None)
+            } else {
+                if !inner.wakers.iter().any(|x| x.will_wake(cx.waker())) {
+                    inner.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<M> Clone for Watch<M> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            seen: self.seen,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Shared<M> {
+    inner: Mutex<Inner<M>>,
+}
+
+#[derive(Debug)]
+struct Inner<M> {
+    state: M,
+    /// Incremented on every transition.
+    version: u64,
+    /// Whether the driver has stopped.
+    closed: bool,
+    /// The tasks waiting for the state to change.
+    wakers: Vec<Waker>,
+}
+
+impl<M> Shared<M> {
+    /// Panics of the observers do not prevent the state from being updated.
+    fn lock(&self) -> MutexGuard<'_, Inner<M>> {
+        self.inner.lock().unwrap_or_else(|x| x.into_inner())
+    }
+}
+
+/// Updates the state seen by the watches, and closes them when dropped.
+struct Publisher<M>(Arc<Shared<M>>);
+
+impl<M> Publisher<M> {
+    /// Updates the shared state and wakes the waiting tasks once it is
+    /// unlocked.
+    fn update(&self, f: impl FnOnce(&mut Inner<M>)) {
+        let mut inner = self.0.lock();
+        f(&mut inner);
+        let wakers = core::mem::take(&mut inner.wakers);
+        drop(inner);
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl<M: Clone> Publisher<M> {
+    fn publish(&self, state: &M) {
+        self.update(|inner| {
+            inner.state = state.clone();
+            inner.version += 1;
+        });
+    }
+}
+
+impl<M> Drop for Publisher<M> {
+    fn drop(&mut self) {
+        self.update(|inner| inner.closed = true);
+    }
+}
@@ -29,6 +29,16 @@ developer to provide a strict state machine definition, e.g. specify its:
 ### Non-default
 
 - `diagram` - generate Mermaid state diagrams in the doc strings. See below.
+- `dot` - generate Graphviz state diagrams available at runtime. See below.
+- `plantuml` - generate PlantUML state diagrams available at runtime. See below.
+- `scxml` - generate SCXML documents available at runtime. See below.
+- `tracing` - trace state transitions with [`tracing`][tracing]. See below.
+- `metrics` - report state transitions with [`metrics`][metrics]. Implies
+  `std`. See below.
+- `serde` - allow deriving `Serialize` and `Deserialize` for the generated types
+  with [`serde`][serde]. See below.
+- `async` - drive state machines with streams of inputs and sinks of outputs.
+  Implies `std`. See below.
 
 ## Usage in `no_std` environments
 
@@ -82,7 +92,8 @@ This code sample:
   input when in the `HalfOpen` state, the machine must move to the `Closed`
   state;
 * Defines outputs. For example: on receiving `Unsuccessful` in the
-  `Closed` state, the machine must output `SetupTimer`.
+  `Closed` state, the machine must output `SetupTimer`. A transition can have
+  several outputs, e.g. `Closed => Unsuccessful => Open [SetupTimer, NotifyOps]`.
 
 This state machine can be used as follows:
 
@@ -137,6 +148,367 @@ state_machine! {
 
 The default visibility is private.
 
+#### Initial state
+
+The initial state can be specified in parentheses after the name of the state
+type:
+
+```rust
+use rust_fsm::*;
+
+state_machine! {
+    CircuitBreaker(Closed) => Result => Action
+
+    Closed => Unsuccessful => Open [SetupTimer],
+    Open => TimerTriggered => HalfOpen,
+    HalfOpen => {
+        Successful => Closed,
+        Unsuccessful => Open [SetupTimer]
+    }
+}
+
+let machine = CircuitBreaker::default();
+assert!(matches!(machine, CircuitBreaker::Closed));
+```
+
+This implements `Default` for the state type and generates the
+`CircuitBreaker::initial()` constructor (the latter is not generated for
+custom state types). With the `diagram` feature the initial state is marked
+in the diagram.
+
+#### Final states
+
+States that the machine is not supposed to leave can be marked as final:
+
+```rust
+use rust_fsm::*;
+
+state_machine! {
+    Door(Open) => Action => Output
+
+    Open => Key => Closed,
+    Closed => Key => Open,
+    Open => Break => Broken,
+    Closed => Break => Broken,
+    final Broken,
+}
+
+let mut machine = Door::default();
+assert!(!machine.is_final());
+assert!(machine.consume(Action::Break).is_ok());
+assert!(machine.is_final());
+```
+
+An `is_final()` method is generated for the state type (unless a custom state
+type is used), and with the `diagram` feature the final states are marked in
+the diagram. Defining a transition from a final state is a compile error:
+
+```rust,compile_fail
+use rust_fsm::*;
+
+state_machine! {
+    Door(Open) => Action => Output
+
+    Open => Break => Broken,
+    // error: final state `Broken` cannot have outgoing transitions
+    Broken => Repair => Open,
+    final Broken,
+}
+```
+
+#### Entry and exit actions
+
+Outputs can be attached to entering or leaving a state, regardless of the
+input that caused the transition:
+
+```rust
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug)] CircuitBreaker(Closed) =>
+    #[derive(Debug)] Result =>
+    #[derive(Debug, PartialEq)] Action
+
+    Closed => Unsuccessful => Open [NotifyOps],
+    Open => TimerTriggered => HalfOpen,
+    HalfOpen => {
+        Successful => Closed,
+        Unsuccessful => Open
+    },
+    enter Open [SetupTimer],
+    exit Open [CancelTimer],
+}
+
+let mut machine = CircuitBreaker::default();
+let outputs = machine.consume(Result::Unsuccessful).unwrap();
+assert!(outputs.into_iter().eq([Action::NotifyOps, Action::SetupTimer]));
+```
+
+When a transition is performed the exit actions of the old state come first,
+then the output of the transition itself, then the entry actions of the new
+state. Transitions to `_` do not leave the state and do not perform these
+actions. If any transition may produce more than one output, `consume` and
+`transition` return them as [`Outputs`] instead of an `Option`.
+
+#### Superstates
+
+States can be grouped into superstates with `state Parent { Child1, Child2 }`.
+Superstates can be nested. Transitions declared for a superstate are inherited
+by all states it contains, unless a state declares its own transition for the
+same input:
+
+```rust
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug, PartialEq)]
+    Connection(Disconnected) => Event => Effect
+
+    Disconnected => Connect => Connected,
+    Connected => Disconnect => Disconnected,
+    Idle => Request => Busy,
+    Busy => Done => Draining,
+    Draining => Disconnect => _ [Refused],
+    state Connected { Idle, Busy, Draining },
+}
+
+let mut machine = Connection::default();
+let _ = machine.consume(Event::Connect);
+assert_eq!(machine, Connection::Idle);
+assert_eq!(machine.parent(), Some("Connected"));
+let _ = machine.consume(Event::Request);
+let _ = machine.consume(Event::Disconnect);
+assert_eq!(machine, Connection::Disconnected);
+```
+
+Only the states inside superstates are generated as variants of the state
+type. Transitioning into a superstate enters its first state. Entry and exit
+actions of a superstate are performed when entering it from the outside and
+leaving it to the outside respectively. The generated `parent()` method returns
+the name of the superstate that contains the state, and the diagram shows
+superstates as composite states.
+
+#### Transitions from any state
+
+A transition can start from any state by using `_` as its source, optionally
+excluding some states with `except`:
+
+```rust
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug, PartialEq)]
+    Job(Idle) => Command => Effect
+
+    Idle => Start => Running,
+    Running => Pause => Paused,
+    Paused => Resume => Running,
+    _ => Reset => Idle [Cleanup],
+    _ except Idle => Finish => Done,
+    final Done,
+}
+
+let mut machine = Job::default();
+assert!(machine.consume(Command::Finish).is_err());
+let _ = machine.consume(Command::Start);
+let _ = machine.consume(Command::Pause);
+let _ = machine.consume(Command::Reset);
+assert_eq!(machine, Job::Idle);
+```
+
+Such transitions apply to every state except the final ones and the excluded
+ones (excluding a superstate excludes all the states it contains). The
+transitions declared for a state itself or for its superstates take precedence.
+
+#### Analysis
+
+The macro warns about the problems in the definition of the machine:
+
+* states that cannot be reached from the initial state;
+* states without outgoing transitions that are not marked as final;
+* inputs that cannot be consumed in any of the reachable states;
+* guarded transitions shadowed by an earlier unguarded transition for the same
+  state and input.
+
+The checks that depend on reachability are only performed if the initial state
+is declared. Guards are not evaluated, so guarded transitions are considered
+possible. These warnings become errors with the `#[fsm(strict)]` attribute:
+
+```rust,compile_fail
+use rust_fsm::*;
+
+state_machine! {
+    #[fsm(strict)]
+    Door(Closed) => Action => Effect
+
+    Closed => Open => Opened,
+    Opened => Close => Closed,
+    // error: state `Locked` is unreachable from the initial state
+    Locked => Unlock => Closed,
+}
+```
+
+The warnings are reported as uses of deprecated items, so they can also be
+denied with the `deprecated` lint:
+
+```rust,compile_fail
+#![deny(deprecated)]
+use rust_fsm::*;
+
+state_machine! {
+    Door(Closed) => Action => Effect
+
+    Closed => {
+        Kick(u8 => _) => Broken,
+        // error: the guarded transition is shadowed by the unguarded one
+        Kick(n if n > 3) => Opened,
+    },
+    Opened => Close => Closed,
+    final Broken,
+}
+```
+
+Unguarded transitions declared more than once for the same state and input are
+always errors. Patterns that match any data, binding it or not, are compared by
+the name of the variant:
+
+```rust,compile_fail
+use rust_fsm::*;
+
+state_machine! {
+    Door(Closed) => Action => Effect
+
+    Closed => {
+        Kick(u8 => n) => Broken,
+        // error: duplicate transition from `Closed` on `Kick`
+        Kick(m) => Opened,
+    },
+    Opened => Close => Closed,
+    final Broken,
+}
+```
+
+#### Total state machines
+
+With the `#[fsm(total)]` attribute every state must have a transition on every
+input, otherwise the macro reports the missing combinations. Transitions with
+guards or refutable patterns do not count. Final states are exempt from this
+requirement: they ignore any input.
+
+Total machines implement the [`TotalStateMachine`] trait, whose transitions
+cannot fail:
+
+```rust
+use rust_fsm::*;
+
+state_machine! {
+    #[fsm(total)]
+    #[derive(Debug, PartialEq)]
+    Switch(Off) => Action => Effect
+
+    Off => {
+        Toggle => On,
+        Reset => _,
+    },
+    On => {
+        Toggle => Off,
+        Reset => Off,
+    },
+}
+
+let mut machine = Switch::default();
+machine.consume_total(Action::Toggle);
+assert_eq!(machine, Switch::On);
+let (machine, _) = machine.transition_total(Action::Reset);
+assert_eq!(machine, Switch::Off);
+```
+
+The final states still have no transitions, so `StateMachine::transition`
+rejects any input in them with a `TransitionImpossibleError`, the same as for
+any other machine, which lets the callers notice that the machine has finished.
+`transition_total` cannot fail, so it keeps a final state without producing
+any outputs instead.
+
+#### Reflection
+
+The macro implements the [`StateMachineMeta`] trait, which describes the
+definition of the machine at runtime: the names of the states, inputs and
+outputs, the initial and final states, and the transitions.
+
+```rust
+use rust_fsm::*;
+
+state_machine! {
+    Door(Closed) => Action => Effect
+
+    Closed => Open => Opened [Creak],
+    Opened => Close => Closed,
+}
+
+assert_eq!(Door::STATES, ["Closed", "Opened"]);
+assert_eq!(Door::INITIAL_STATE, Some("Closed"));
+let transition = &Door::transition_table()[0];
+assert_eq!(transition.source, "Closed");
+assert_eq!(transition.input, "Open");
+assert_eq!(transition.target, "Opened");
+assert_eq!(transition.outputs, ["Creak"]);
+```
+
+The transitions of superstates and `_` transitions are listed for every state
+they apply to, in the order the transitions are tried.
+
+#### Queries
+
+The state type gets methods to check which inputs can be consumed without
+performing a transition:
+
+```rust
+use rust_fsm::*;
+
+state_machine! {
+    Door(Closed) => Action => Effect
+
+    Closed => {
+        Open => Opened,
+        Lock(u8 => code if code > 0) => Locked,
+    },
+    Opened => Close => Closed,
+    Locked => Unlock => Closed,
+}
+
+let door = Door::default();
+assert!(door.can_consume(&Action::Open));
+assert!(!door.can_consume(&Action::Close));
+assert_eq!(door.accepted_input_names(), ["Lock", "Open"]);
+```
+
+`can_consume` only borrows the state and the input, so by default it does not
+evaluate the guards, which are written for the data itself. With the
+`#[fsm(guards_by_ref)]` attribute it evaluates them on references to the data
+instead, so the guards of such a machine must compile with the bindings being
+references as well, e.g. `code.0 > 0` rather than `code > 0`:
+
+```rust
+use rust_fsm::*;
+
+pub struct Code(u8);
+
+state_machine! {
+    #[fsm(guards_by_ref)]
+    Door(Closed) => Action => Effect
+
+    Closed => Lock(Code => code if code.0 > 0) => Locked,
+    Locked => Unlock => Closed,
+}
+
+let door = Door::default();
+assert!(door.can_consume(&Action::Lock(Code(1))));
+assert!(!door.can_consume(&Action::Lock(Code(0))));
+```
+
+`accepted_input_names` does not take the guards into account. These methods are
+not generated for custom state types.
+
 #### Custom alphabet types
 
 You can supply your own types to use as input, output or state. All of them are
@@ -179,7 +551,9 @@ state_machine! {
 `state_machine` macro can document your state machines with diagrams. This is
 controlled by the `diagram` feature, which is non-default. The diagrams are
 generated in the [Mermaid][mermaid] format. This feature includes the Mermaid
-script into the documentation page.
+script into the documentation page. Transitions are labelled as
+`Input [guard] / Outputs`, and the types of the data carried by states are
+shown in notes.
 
 To see this in action, download the repository and run:
 
@@ -189,6 +563,241 @@ cargo doc -p doc-example --open
 
 ![image](doc-diagram-example.png)
 
+Regardless of this feature, the macro implements the `MermaidDiagram` trait, so
+the same script is available at runtime, e.g. for serving it on a debug
+endpoint or for snapshot tests:
+
+```rust
+use rust_fsm::*;
+
+state_machine! {
+    Door(Closed) => Action => Effect
+
+    Closed => Open => Opened,
+    Opened => Close => Closed,
+}
+
+assert!(Door::MERMAID.starts_with("stateDiagram-v2"));
+```
+
+With the `std` feature, `render_mermaid_with_current()` renders the diagram with
+the current state highlighted, optionally along with the last transition taken.
+
+The `dot` feature, which is also non-default, makes the macro implement the
+`DotDiagram` trait with the diagram in the DOT language of Graphviz. It does not
+add any dependencies:
+
+```rust,ignore
+use rust_fsm::*;
+
+std::fs::write("circuit_breaker.dot", CircuitBreaker::DOT)?;
+```
+
+Similarly, the `plantuml` feature implements the `PlantUmlDiagram` trait with a
+[PlantUML][plantuml] diagram, and the `scxml` feature implements the
+`ScxmlDocument` trait with a W3C [SCXML][scxml] document for the tools that
+understand it. Guards become the conditions of the transitions, and outputs
+become actions (`send` elements in SCXML):
+
+```rust,ignore
+use rust_fsm::*;
+
+std::fs::write("circuit_breaker.puml", CircuitBreaker::PLANTUML)?;
+std::fs::write("circuit_breaker.scxml", CircuitBreaker::SCXML)?;
+```
+
+#### Importing from files
+
+Machines authored as SCXML documents (`.scxml` or `.xml`) or Mermaid state
+diagrams (`.mmd` or `.mermaid`) can be imported with the
+`state_machine_from_file` macro, which produces the same code as
+`state_machine`. The path is relative to the directory of the crate manifest,
+and it can be followed by the header of the machine:
+
+```rust,ignore
+use rust_fsm::*;
+
+state_machine_from_file!(
+    "machines/door.mmd",
+    #[derive(Debug)]
+    pub Door => pub Action => pub Effect
+);
+
+// Without a header, the machine is named after the `name` attribute of the
+// SCXML document or after the file: `Turnstile`, `TurnstileInput` and
+// `TurnstileOutput`.
+state_machine_from_file!("machines/turnstile.scxml");
+```
+
+The initial state is taken from the file unless the header declares it.
+Mermaid transitions are labelled with their inputs followed by the outputs, e.g.
+`Closed --> Open: Unsuccessful / SetupTimer`, and entry and exit actions are
+written as `Open: enter / StartTimer`. SCXML transitions use their `event` as
+the input, and the `send` and `raise` elements as outputs. The constructs that
+cannot be expressed in the DSL, such as concurrent states, conditions or
+executable content, are reported as errors pointing to the line of the file.
+
+### Serialization
+
+With the `serde` feature, the state, input and output types generated for a
+machine declared with the `#[fsm(serde)]` attribute derive `Serialize` and
+`Deserialize` from [`serde`][serde], so a machine can be stored and its inputs
+replayed from elsewhere. The variants are tagged with the names returned by
+their `name()` methods regardless of the `#[serde(...)]` attributes of the
+type, and the data they carry is serialized as the content of the variant:
+
+```rust,ignore
+state_machine! {
+    #[fsm(serde)]
+    CircuitBreaker(Closed) => Result => Action
+    // ...
+}
+
+let machine = CircuitBreaker::Open;
+assert_eq!(serde_json::to_string(&machine)?, r#""Open""#);
+```
+
+Machines without the attribute are not affected by the feature, so their types
+can derive the traits themselves. The types provided with the `::path` syntax
+are not generated, so they need to derive the traits in any case. The default
+representation of `serde` uses the same tags.
+
+#### Snapshots
+
+`StateMachineMeta::DEFINITION_HASH` is a hash of the states and the
+transitions of a machine computed by the macro. `Snapshot` stores it along with
+the state, so the machine can be restored after its definition changed: in that
+case the name of the stored state is passed to a migration hook returning the
+new name of a renamed state, and a state that no longer exists is reported as
+`RestoreError::UnknownState`. Requires the `std` feature as well:
+
+```rust,ignore
+use rust_fsm::*;
+
+let stored = serde_json::to_string(&Snapshot::new(&machine))?;
+
+let mut deserializer = serde_json::Deserializer::from_str(&stored);
+let machine = Snapshot::<CircuitBreaker>::restore(&mut deserializer, |state| match state {
+    "Broken" => Some("Open"),
+    _ => None,
+})?;
+```
+
+### Replaying inputs
+
+When the inputs are stored rather than the states, `replay` rebuilds the state
+by consuming a log of inputs starting from the initial state, and returns the
+final state along with all the outputs produced. The first input the machine
+cannot consume is reported with its position in the log, while
+`replay_lenient` skips such inputs and collects them. Requires the `std`
+feature:
+
+```rust,ignore
+use rust_fsm::*;
+
+let log = [CircuitBreakerInput::Unsuccessful, CircuitBreakerInput::TimerTriggered];
+let replayed = replay(CircuitBreaker::Closed, log)?;
+assert_eq!(replayed.state, CircuitBreaker::HalfOpen);
+```
+
+### Transition history
+
+`Recorded` wraps a state machine and records its last `N` transitions in a ring
+buffer, which helps to find out how a machine got into its current state. It
+does not allocate, so it can be used in `no_std` environments, and with the
+`std` feature every record has a timestamp. By default the states, inputs and
+outputs are recorded by their names, and a custom `Projection` can record
+anything else about them:
+
+```rust,ignore
+use rust_fsm::*;
+
+let mut machine = Recorded::<CircuitBreaker, 16>::new(CircuitBreaker::Closed);
+machine.consume(CircuitBreakerInput::Unsuccessful)?;
+
+for record in machine.history() {
+    println!("{} --{}--> {} {:?}", record.from, record.input, record.to, record.outputs);
+}
+```
+
+### Observing transitions
+
+`Observed` wraps a state machine and notifies a `TransitionObserver` about its
+transitions and the inputs it rejects, so logging, metrics and the like can be
+attached once instead of around every call to `consume()`. Closures taking the
+old state, the input, the new state and the outputs are observers as well, and a
+pair of observers notifies both of them. The observer receives the old state
+and the input after the transition, so both must implement `Clone`:
+
+```rust,ignore
+use rust_fsm::*;
+
+struct Log;
+
+impl TransitionObserver<CircuitBreaker> for Log {
+    fn on_rejected(&mut self, state: &CircuitBreaker, input: &CircuitBreakerInput) {
+        eprintln!("{} rejected {}", state.name(), input.name());
+    }
+}
+
+let mut machine = Observed::new(CircuitBreaker::Closed, Log);
+```
+
+### Tracing
+
+With the `tracing` feature, `Traced` wraps a state machine and emits an
+info-level event for every transition with the name of the machine and the
+names of the states, the input and the outputs, and a warning for every input
+the machine cannot consume:
+
+```rust,ignore
+use rust_fsm::*;
+
+let mut machine = Traced::with_name(CircuitBreaker::Closed, "backend");
+machine.consume(CircuitBreakerInput::Unsuccessful)?;
+```
+
+### Metrics
+
+With the `metrics` feature, `Metered` wraps a state machine and reports its
+transitions with [`metrics`][metrics] under the name of the machine: the
+`rust_fsm_transitions_total` counter labelled with the source state and the
+input, the `rust_fsm_rejections_total` counter of the inputs the machine cannot
+consume and the `rust_fsm_state_seconds` gauge of the time spent in each state,
+increased when the machine leaves the state:
+
+```rust,ignore
+use rust_fsm::*;
+
+let mut machine = Metered::with_name(CircuitBreaker::Closed, "backend");
+machine.consume(CircuitBreakerInput::Unsuccessful)?;
+```
+
+### Async
+
+With the `async` feature, `Driver` runs a state machine asynchronously without
+depending on any particular executor: it consumes the inputs received from a
+[`Stream`][stream] and sends the outputs to a [`Sink`][sink] until the stream
+ends, the machine cannot consume an input or the outputs cannot be sent. The
+state can be observed while the machine is running with a `Watch` handle:
+
+```rust,ignore
+use rust_fsm::*;
+
+let driver = Driver::new(CircuitBreaker::Closed, inputs, outputs);
+let mut watch = driver.watch();
+spawn(async move {
+    while let Some(state) = watch.changed().await {
+        println!("{state:?}");
+    }
+});
+let machine = driver.run().await?;
+```
+
+When the outputs cannot be sent the transition producing them has already been
+performed, so `DriverError::Sink` carries the machine in its new state along
+with the error of the sink.
+
 ### Without DSL
 
 The `state_machine` macro has limited capabilities (for example, a state
@@ -208,24 +817,57 @@ You can see an example of the Circuit Breaker state machine in the
 [crate-badge]: https://img.shields.io/crates/v/rust-fsm.svg
 [crate-link]: https://crates.io/crates/rust-fsm
 [mermaid]: https://mermaid.js.org/
+[plantuml]: https://plantuml.com/state-diagram
+[scxml]: https://www.w3.org/TR/scxml/
+[tracing]: https://docs.rs/tracing
+[metrics]: https://docs.rs/metrics
+[serde]: https://serde.rs
+[stream]: https://docs.rs/futures/latest/futures/stream/trait.Stream.html
+[sink]: https://docs.rs/futures/latest/futures/sink/trait.Sink.html
 */
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use core::{
-    fmt::{self, Debug},
-    marker::PhantomData,
-};
+use core::fmt::{self, Debug};
 #[cfg(feature = "std")]
 use std::error::Error;
 
 use replace_with::replace_with_or_abort_and_return;
 #[cfg(feature = "dsl")]
-pub use rust_fsm_dsl::state_machine;
+pub use rust_fsm_dsl::{state_machine, state_machine_from_file};
 
 #[cfg(feature = "diagram")]
 pub use aquamarine::aquamarine;
 
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
+
+#[cfg(feature = "async")]
+mod driver;
+#[cfg(feature = "metrics")]
+mod metered;
+mod observed;
+mod recorded;
+#[cfg(feature = "std")]
+mod replay;
+#[cfg(all(feature = "serde", feature = "std"))]
+mod snapshot;
+#[cfg(feature = "tracing")]
+mod traced;
+#[cfg(feature = "async")]
+pub use driver::{Driver, DriverError, Watch};
+#[cfg(feature = "metrics")]
+pub use metered::Metered;
+pub use observed::{Observed, TransitionObserver};
+pub use recorded::{Names, Projection, Record, RecordOf, Recorded};
+#[cfg(feature = "std")]
+pub use replay::{replay, replay_lenient, Replay, ReplayError};
+#[cfg(all(feature = "serde", feature = "std"))]
+pub use snapshot::{RestoreError, Snapshot};
+#[cfg(feature = "tracing")]
+pub use traced::Traced;
+
 /// This trait is designed to describe any possible deterministic finite state
 /// machine/transducer. This is just a formal definition that may be
 /// inconvenient to be used in practical programming, but it is used throughout
@@ -235,17 +877,22 @@ pub trait StateMachine: Sized {
     type Input<'i>;
     /// The output alphabet.
     type Output<'i>;
+    /// The outputs produced by a single transition. This is usually
+    /// `Option<Self::Output<'i>>`, or [`Outputs`] if a transition may produce
+    /// more than one output.
+    type Outputs<'i>: IntoIterator<Item = Self::Output<'i>>;
     /// The transition fuction that outputs a new state based on the current
     /// state and the provided input. Outputs [`Err`] (allowing recovery of the state and input)
     /// when there is no transition for a given combination of the input and the state.
-    /// Also gives you the output, if any.
+    /// Also gives you the outputs, if any.
     ///
     /// This function is discouraged from panicking.
+    #[allow(clippy::type_complexity)]
     fn transition<'i>(
         self,
         input: Self::Input<'i>,
-    ) -> Result<(Self, Option<Self::Output<'i>>), TransitionImpossibleError<Self, Self::Input<'i>>>;
-    /// Consumes the provided input, gives an output and performs a state
+    ) -> Result<(Self, Self::Outputs<'i>), TransitionImpossibleError<Self, Self::Input<'i>>>;
+    /// Consumes the provided input, gives the outputs and performs a state
     /// transition. If a state transition with the current state and the
     /// provided input is not allowed, returns an error containing the input.
     ///
@@ -253,8 +900,7 @@ pub trait StateMachine: Sized {
     fn consume<'me, 'i>(
         &'me mut self,
         input: Self::Input<'i>,
-    ) -> Result<Option<Self::Output<'i>>, TransitionImpossibleError_<'me, Self, Self::Input<'i>>>
-    {
+    ) -> Result<Self::Outputs<'i>, TransitionImpossibleError_<'me, Self, Self::Input<'i>>> {
         replace_with_or_abort_and_return(self, |x| match x.transition(input) {
             Ok((state, ret)) => (Ok(ret), state),
             Err(TransitionImpossibleError { state, input }) => (Err(input), state),
@@ -263,6 +909,216 @@ pub trait StateMachine: Sized {
     }
 }
 
+/// Static information about a state machine, allowing to inspect its definition
+/// at runtime. Implemented by the `state_machine` macro. All the states, inputs
+/// and outputs are referred to by their names.
+pub trait StateMachineMeta: StateMachine {
+    /// The names of the states.
+    const STATES: &'static [&'static str];
+    /// The names of the inputs.
+    const INPUTS: &'static [&'static str];
+    /// The names of the outputs.
+    const OUTPUTS: &'static [&'static str];
+    /// The state the machine starts in, if declared.
+    const INITIAL_STATE: Option<&'static str>;
+    /// The final (accepting) states.
+    const FINAL_STATES: &'static [&'static str];
+    /// All the transitions in the order they are tried. The transitions of
+    /// superstates and `_` transitions are listed for every state they apply
+    /// to.
+    const TRANSITIONS: &'static [TransitionInfo];
+    /// A hash of the states and the transitions of the machine, which changes
+    /// whenever the definition does. Stored in a [`Snapshot`] to detect that
+    /// the machine changed since.
+    const DEFINITION_HASH: u64;
+
+    /// The name of the current state.
+    fn state_name(&self) -> &'static str;
+
+    /// The name of the input.
+    fn input_name(input: &Self::Input<'_>) -> &'static str;
+
+    /// The name of the output.
+    fn output_name(output: &Self::Output<'_>) -> &'static str;
+
+    /// The transitions of the machine, see [`StateMachineMeta::TRANSITIONS`].
+    fn transition_table() -> &'static [TransitionInfo] {
+        Self::TRANSITIONS
+    }
+}
+
+/// A transition of a state machine, see [`StateMachineMeta`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransitionInfo {
+    /// The state the transition starts from.
+    pub source: &'static str,
+    /// The input that causes the transition.
+    pub input: &'static str,
+    /// The state the transition leads to. This is the same as `source` for
+    /// the transitions that keep the current state.
+    pub target: &'static str,
+    /// The outputs produced by the transition, including the exit and entry
+    /// actions of the states.
+    pub outputs: &'static [&'static str],
+    /// Whether the transition is only taken if its guard holds.
+    pub guarded: bool,
+}
+
+/// A state machine with a [Mermaid][mermaid] state diagram, implemented by the
+/// `state_machine` macro.
+///
+/// [mermaid]: https://mermaid.js.org/
+pub trait MermaidDiagram: StateMachineMeta {
+    /// The source of the diagram.
+    const MERMAID: &'static str;
+
+    /// Renders the diagram with the current state highlighted. If the last
+    /// transition is provided, the state it started from is highlighted as
+    /// well, and the current state is annotated with its input.
+    #[cfg(feature = "std")]
+    fn render_mermaid_with_current(&self, last_transition: Option<&TransitionInfo>) -> String {
+        let current = self.state_name();
+        let mut diagram = String::from(Self::MERMAID);
+        diagram.push_str("\n    classDef current stroke:#e4572e,stroke-width:3px,fill:#f6c9bd");
+        diagram.push_str(&format!("\n    class {current} current"));
+        if let Some(transition) = last_transition {
+            if transition.source != current {
+                diagram.push_str("\n    classDef previous stroke:#e4572e,stroke-dasharray:4 4");
+                diagram.push_str(&format!("\n    class {} previous", transition.source));
+            }
+            diagram.push_str(&format!(
+                "\n    note right of {current}: last input: {}",
+                transition.input
+            ));
+        }
+        diagram
+    }
+}
+
+/// A state machine with a diagram in the DOT language of [Graphviz][graphviz],
+/// implemented by the `state_machine` macro with the `dot` feature.
+///
+/// [graphviz]: https://graphviz.org/
+#[cfg(feature = "dot")]
+pub trait DotDiagram {
+    /// The source of the diagram.
+    const DOT: &'static str;
+}
+
+/// A state machine with a [PlantUML][plantuml] state diagram, implemented by
+/// the `state_machine` macro with the `plantuml` feature.
+///
+/// [plantuml]: https://plantuml.com/state-diagram
+#[cfg(feature = "plantuml")]
+pub trait PlantUmlDiagram {
+    /// The source of the diagram.
+    const PLANTUML: &'static str;
+}
+
+/// A state machine described as a [SCXML][scxml] document, implemented by the
+/// `state_machine` macro with the `scxml` feature.
+///
+/// [scxml]: https://www.w3.org/TR/scxml/
+#[cfg(feature = "scxml")]
+pub trait ScxmlDocument {
+    /// The source of the document.
+    const SCXML: &'static str;
+}
+
+/// A state machine that has a transition for every combination of a state and
+/// an input, so its state transitions cannot fail. The `state_machine` macro
+/// implements it for the machines declared with `#[fsm(total)]`.
+pub trait TotalStateMachine: StateMachine {
+    /// The infallible counterpart of [`StateMachine::transition`].
+    ///
+    /// Final states have no transitions: [`StateMachine::transition`] rejects
+    /// any input in them, while this function keeps the state and produces no
+    /// outputs.
+    fn transition_total<'i>(self, input: Self::Input<'i>) -> (Self, Self::Outputs<'i>);
+    /// The infallible counterpart of [`StateMachine::consume`].
+    ///
+    /// Aborts if `transition_total` panics.
+    fn consume_total<'i>(&mut self, input: Self::Input<'i>) -> Self::Outputs<'i> {
+        replace_with_or_abort_and_return(self, |x| {
+            let (state, outputs) = x.transition_total(input);
+            (outputs, state)
+        })
+    }
+}
+
+/// A fixed-capacity collection of the outputs produced by a single state
+/// transition, in the order they were produced. This is what the
+/// `state_machine` macro uses when a transition may produce more than one
+/// output (e.g. when states have entry or exit actions).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outputs<T, const N: usize>([Option<T>; N]);
+
+impl<T, const N: usize> Outputs<T, N> {
+    /// Iterates over the outputs by reference.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.0.iter().flatten()
+    }
+    /// The number of outputs.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+    /// Whether the transition produced no outputs.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(Option::is_none)
+    }
+}
+
+/// The outputs of a transition that can be inspected without consuming them.
+/// Implemented for `Option` and [`Outputs`].
+pub trait OutputsRef<T> {
+    /// Iterates over the outputs by reference.
+    fn iter_outputs<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a;
+}
+
+impl<T> OutputsRef<T> for Option<T> {
+    fn iter_outputs<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+}
+
+impl<T, const N: usize> OutputsRef<T> for Outputs<T, N> {
+    fn iter_outputs<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+}
+
+impl<T, const N: usize> From<[Option<T>; N]> for Outputs<T, N> {
+    fn from(outputs: [Option<T>; N]) -> Self {
+        Self(outputs)
+    }
+}
+
+impl<T, const N: usize> IntoIterator for Outputs<T, N> {
+    type Item = T;
+    type IntoIter = core::iter::Flatten<core::array::IntoIter<Option<T>, N>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter().flatten()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a Outputs<T, N> {
+    type Item = &'a T;
+    type IntoIter = core::iter::Flatten<core::slice::Iter<'a, Option<T>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().flatten()
+    }
+}
+
 #[derive(Debug, Clone)]
 /// An error type that represents that the state transition is impossible given
 /// the current combination of state and input.
@@ -0,0 +1,118 @@
+use std::time::Instant;
+
+use core::fmt::{self, Debug};
+
+use crate::{StateMachine, StateMachineMeta, TransitionImpossibleError};
+
+/// A state machine reporting its transitions with [`metrics`]. Requires the
+/// `metrics` feature. The following metrics are labelled with the name of the
+/// machine:
+///
+/// * `rust_fsm_transitions_total`: a counter of the transitions performed,
+///   labelled with the `from` state and the `input`;
+/// * `rust_fsm_rejections_total`: a counter of the inputs the machine could not
+///   consume, labelled with the `state` and the `input`;
+/// * `rust_fsm_state_seconds`: a gauge of the time spent in each `state`,
+///   increased when the machine leaves the state.
+///
+/// ```rust
+/// use rust_fsm::*;
+///
+/// state_machine! {
+///     #[derive(Debug)]
+///     Door(Closed) => #[derive(Debug)] Action => Effect
+///
+///     Closed => Open => Opened,
+///     Opened => Close => Closed,
+/// }
+///
+/// let mut door = Metered::with_name(Door::Closed, "garage");
+/// door.consume(Action::Open).unwrap();
+/// ```
+pub struct Metered<M> {
+    machine: M,
+    name: &'static str,
+    /// When the machine entered its current state.
+    entered: Instant,
+}
+
+impl<M> Metered<M> {
+    /// Reports the transitions of the machine, naming it after its type.
+    pub fn new(machine: M) -> Self {
+        Self::with_name(machine, core::any::type_name::<M>())
+    }
+
+    /// Reports the transitions of the machine under the provided name.
+    pub fn with_name(machine: M, name: &'static str) -> Self {
+        Self {
+            machine,
+            name,
+            entered: Instant::now(),
+        }
+    }
+
+    /// The current state of the machine.
+    pub fn state(&self) -> &M {
+        &self.machine
+    }
+
+    /// Stops reporting the transitions of the machine and gives it back.
+    pub fn into_inner(self) -> M {
+        self.machine
+    }
+}
+
+impl<M: Debug> Debug for Metered<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Metered")
+            .field("machine", &self.machine)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: StateMachineMeta> StateMachine for Metered<M> {
+    type Input<'i> = M::Input<'i>;
+    type Output<'i> = M::Output<'i>;
+    type Outputs<'i> = M::Outputs<'i>;
+
+    fn transition<'i>(
+        mut self,
+        input: Self::Input<'i>,
+    ) -> Result<(Self, Self::Outputs<'i>), TransitionImpossibleError<Self, Self::Input<'i>>> {
+        let from = self.machine.state_name();
+        let input_name = M::input_name(&input);
+        match self.machine.transition(input) {
+            Ok((machine, outputs)) => {
+                let now = Instant::now();
+                metrics::counter!(
+                    "rust_fsm_transitions_total",
+                    "machine" => self.name,
+                    "from" => from,
+                    "input" => input_name,
+                )
+                .increment(1);
+                metrics::gauge!(
+                    "rust_fsm_state_seconds",
+                    "machine" => self.name,
+                    "state" => from,
+                )
+                .increment(now.duration_since(self.entered).as_secs_f64());
+                self.machine = machine;
+                self.entered = now;
+                Ok((self, outputs))
+            }
+            Err(TransitionImpossibleError { state, input }) => {
+                metrics::counter!(
+                    "rust_fsm_rejections_total",
+                    "machine" => self.name,
+                    "state" => from,
+                    "input" => input_name,
+                )
+                .increment(1);
+                self.machine = state;
+                Err(TransitionImpossibleError { state: self, input })
+            }
+        }
+    }
+}
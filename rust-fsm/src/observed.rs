@@ -0,0 +1,159 @@
+use core::fmt::{self, Debug};
+
+use crate::{StateMachine, TransitionImpossibleError};
+
+/// Receives the transitions of an [`Observed`] state machine. Both methods do
+/// nothing by default.
+///
+/// Closures taking the old state, the input, the new state and the outputs
+/// implement this trait as well.
+pub trait TransitionObserver<M: StateMachine> {
+    /// Called after a transition from the `from` state to the `to` state.
+    fn on_transition<'i>(
+        &mut self,
+        from: &M,
+        input: &M::Input<'i>,
+        to: &M,
+        outputs: &M::Outputs<'i>,
+    ) {
+        let _ = (from, input, to, outputs);
+    }
+
+    /// Called when there is no transition from the state on the input.
+    fn on_rejected(&mut self, state: &M, input: &M::Input<'_>) {
+        let _ = (state, input);
+    }
+}
+
+impl<M, F> TransitionObserver<M> for F
+where
+    M: StateMachine,
+    F: for<'i> FnMut(&M, &M::Input<'i>, &M, &M::Outputs<'i>),
+{
+    fn on_transition<'i>(
+        &mut self,
+        from: &M,
+        input: &M::Input<'i>,
+        to: &M,
+        outputs: &M::Outputs<'i>,
+    ) {
+        self(from, input, to, outputs)
+    }
+}
+
+/// Notifies both observers, the first one first.
+impl<M: StateMachine, A: TransitionObserver<M>, B: TransitionObserver<M>> TransitionObserver<M>
+    for (A, B)
+{
+    fn on_transition<'i>(
+        &mut self,
+        from: &M,
+        input: &M::Input<'i>,
+        to: &M,
+        outputs: &M::Outputs<'i>,
+    ) {
+        self.0.on_transition(from, input, to, outputs);
+        self.1.on_transition(from, input, to, outputs);
+    }
+
+    fn on_rejected(&mut self, state: &M, input: &M::Input<'_>) {
+        self.0.on_rejected(state, input);
+        self.1.on_rejected(state, input);
+    }
+}
+
+/// A state machine notifying an observer about its transitions, so logging,
+/// metrics and the like can be attached to the machine once instead of every
+/// place it is used in.
+///
+/// The observer receives the old state and the input after the transition, so
+/// they are cloned before performing it.
+///
+/// ```rust
+/// use rust_fsm::*;
+///
+/// state_machine! {
+///     #[derive(Debug, Clone)]
+///     Door(Closed) => #[derive(Debug, Clone)] Action => Effect
+///
+///     Closed => Open => Opened,
+///     Opened => Close => Closed,
+/// }
+///
+/// let mut transitions = 0;
+/// let mut door = Observed::new(Door::Closed, |_: &Door, _: &Action, _: &Door, _: &Option<Effect>| {
+///     transitions += 1;
+/// });
+/// door.consume(Action::Open).unwrap();
+/// door.consume(Action::Close).unwrap();
+/// drop(door);
+/// assert_eq!(transitions, 2);
+/// ```
+pub struct Observed<M, O> {
+    machine: M,
+    observer: O,
+}
+
+impl<M: StateMachine, O: TransitionObserver<M>> Observed<M, O> {
+    /// Notifies the observer about the transitions of the machine.
+    pub fn new(machine: M, observer: O) -> Self {
+        Self { machine, observer }
+    }
+
+    /// The current state of the machine.
+    pub fn state(&self) -> &M {
+        &self.machine
+    }
+
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+
+    /// Stops observing the machine and gives it back along with the observer.
+    pub fn into_inner(self) -> (M, O) {
+        (self.machine, self.observer)
+    }
+}
+
+impl<M: Debug, O> Debug for Observed<M, O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Observed")
+            .field("machine", &self.machine)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M, O> StateMachine for Observed<M, O>
+where
+    M: StateMachine + Clone,
+    for<'i> M::Input<'i>: Clone,
+    O: TransitionObserver<M>,
+{
+    type Input<'i> = M::Input<'i>;
+    type Output<'i> = M::Output<'i>;
+    type Outputs<'i> = M::Outputs<'i>;
+
+    fn transition<'i>(
+        mut self,
+        input: Self::Input<'i>,
+    ) -> Result<(Self, Self::Outputs<'i>), TransitionImpossibleError<Self, Self::Input<'i>>> {
+        let from = self.machine.clone();
+        match self.machine.transition(input.clone()) {
+            Ok((machine, outputs)) => {
+                self.observer
+                    .on_transition(&from, &input, &machine, &outputs);
+                self.machine = machine;
+                Ok((self, outputs))
+            }
+            Err(TransitionImpossibleError { state, input }) => {
+                self.observer.on_rejected(&state, &input);
+                self.machine = state;
+                Err(TransitionImpossibleError { state: self, input })
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use core::fmt::{self, Debug};
+
+use crate::{OutputsRef, StateMachine, StateMachineMeta, TransitionImpossibleError};
+
+/// Describes how the transitions are stored by [`Recorded`].
+pub trait Projection<M: StateMachine> {
+    /// How the states are stored.
+    type State;
+    /// How the inputs are stored.
+    type Input;
+    /// How the outputs of a single transition are stored.
+    type Outputs;
+
+    fn state(&self, state: &M) -> Self::State;
+    fn input(&self, input: &M::Input<'_>) -> Self::Input;
+    fn outputs(&self, outputs: &M::Outputs<'_>) -> Self::Outputs;
+}
+
+/// Stores the transitions by the names of the states, inputs and outputs. This
+/// is the default projection of [`Recorded`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Names;
+
+impl<M> Projection<M> for Names
+where
+    M: StateMachineMeta,
+    for<'i> M::Outputs<'i>: OutputsRef<M::Output<'i>>,
+{
+    type State = &'static str;
+    type Input = &'static str;
+    type Outputs = &'static [&'static str];
+
+    fn state(&self, state: &M) -> &'static str {
+        state.state_name()
+    }
+
+    fn input(&self, input: &M::Input<'_>) -> &'static str {
+        M::input_name(input)
+    }
+
+    /// Any transition producing the same outputs has a static list of their
+    /// names, so there is no need to allocate one.
+    fn outputs(&self, outputs: &M::Outputs<'_>) -> &'static [&'static str] {
+        M::TRANSITIONS
+            .iter()
+            .map(|x| x.outputs)
+            .find(|names| {
+                names
+                    .iter()
+                    .copied()
+                    .eq(outputs.iter_outputs().map(|x| M::output_name(x)))
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// A transition recorded by [`Recorded`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Record<S, I, O> {
+    /// The state the transition started from.
+    pub from: S,
+    /// The input that caused the transition.
+    pub input: I,
+    /// The state the transition led to.
+    pub to: S,
+    /// The outputs produced by the transition.
+    pub outputs: O,
+    /// When the transition happened.
+    #[cfg(feature = "std")]
+    pub timestamp: std::time::SystemTime,
+}
+
+/// The records of a [`Recorded`] machine.
+pub type RecordOf<M, P> =
+    Record<<P as Projection<M>>::State, <P as Projection<M>>::Input, <P as Projection<M>>::Outputs>;
+
+/// A state machine that records the last `N` transitions it performed. The
+/// records are kept in a ring buffer, so the oldest ones are discarded when
+/// it is full and no allocations are made.
+///
+/// ```rust
+/// use rust_fsm::*;
+///
+/// state_machine! {
+///     #[derive(Debug)]
+///     Door(Closed) => #[derive(Debug)] Action => Effect
+///
+///     Closed => Open => Opened,
+///     Opened => Close => Closed [Click],
+/// }
+///
+/// let mut door = Recorded::<Door, 8>::new(Door::Closed);
+/// door.consume(Action::Open).unwrap();
+/// door.consume(Action::Close).unwrap();
+///
+/// let last = door.history().last().unwrap();
+/// assert_eq!((last.from, last.input, last.to), ("Opened", "Close", "Closed"));
+/// assert_eq!(last.outputs, ["Click"]);
+/// ```
+pub struct Recorded<M: StateMachine, const N: usize, P: Projection<M> = Names> {
+    machine: M,
+    projection: P,
+    records: [Option<RecordOf<M, P>>; N],
+    /// The position of the next record.
+    next: usize,
+}
+
+impl<M: StateMachine, const N: usize, P: Projection<M> + Default> Recorded<M, N, P> {
+    /// Records the transitions of the machine with the default projection.
+    pub fn new(machine: M) -> Self {
+        Self::with_projection(machine, P::default())
+    }
+}
+
+impl<M: StateMachine, const N: usize, P: Projection<M>> Recorded<M, N, P> {
+    /// Records the transitions of the machine with the provided projection.
+    pub fn with_projection(machine: M, projection: P) -> Self {
+        Self {
+            machine,
+            projection,
+            records: core::array::from_fn(|_| None),
+            next: 0,
+        }
+    }
+
+    /// The current state of the machine.
+    pub fn state(&self) -> &M {
+        &self.machine
+    }
+
+    /// Stops recording and gives the machine back.
+    pub fn into_inner(self) -> M {
+        self.machine
+    }
+
+    /// The recorded transitions, the oldest first.
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = &RecordOf<M, P>> {
+        let (newest, oldest) = self.records.split_at(self.next);
+        oldest.iter().chain(newest).flatten()
+    }
+
+    /// Discards the recorded transitions.
+    pub fn clear_history(&mut self) {
+        self.records.iter_mut().for_each(|x| *x = None);
+        self.next = 0;
+    }
+
+    fn record(&mut self, record: RecordOf<M, P>) {
+        if N == 0 {
+            return;
+        }
+        self.records[self.next] = Some(record);
+        self.next = (self.next + 1) % N;
+    }
+}
+
+impl<M: StateMachine + Debug, const N: usize, P: Projection<M>> Debug for Recorded<M, N, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Recorded")
+            .field("machine", &self.machine)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: StateMachine, const N: usize, P: Projection<M>> StateMachine for Recorded<M, N, P> {
+    type Input<'i> = M::Input<'i>;
+    type Output<'i> = M::Output<'i>;
+    type Outputs<'i> = M::Outputs<'i>;
+
+    fn transition<'i>(
+        mut self,
+        input: Self::Input<'i>,
+    ) -> Result<(Self, Self::Outputs<'i>), TransitionImpossibleError<Self, Self::Input<'i>>> {
+        let from = self.projection.state(&self.machine);
+        let recorded_input = self.projection.input(&input);
+        match self.machine.transition(input) {
+            Ok((machine, outputs)) => {
+                self.machine = machine;
+                self.record(Record {
+                    from,
+                    input: recorded_input,
+                    to: self.projection.state(&self.machine),
+                    outputs: self.projection.outputs(&outputs),
+                    #[cfg(feature = "std")]
+                    timestamp: std::time::SystemTime::now(),
+                });
+                Ok((self, outputs))
+            }
+            Err(TransitionImpossibleError { state, input }) => {
+                self.machine = state;
+                Err(TransitionImpossibleError { state: self, input })
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use core::fmt::{self, Debug};
+use std::error::Error;
+
+use crate::{StateMachine, TransitionImpossibleError};
+
+/// The result of replaying a log of inputs with [`replay`] or
+/// [`replay_lenient`]. Requires the `std` feature.
+#[derive(Debug, Clone)]
+pub struct Replay<M, I, O> {
+    /// The state after consuming all the inputs.
+    pub state: M,
+    /// The outputs of all the transitions in the order they were produced.
+    pub outputs: Vec<O>,
+    /// The inputs that were skipped along with their positions in the log.
+    /// Always empty for [`replay`].
+    pub rejected: Vec<(usize, I)>,
+}
+
+/// The first input of the log the machine could not consume, see [`replay`].
+#[derive(Debug, Clone)]
+pub struct ReplayError<M, I> {
+    /// The position of the input in the log.
+    pub index: usize,
+    /// The state the machine was in and the input.
+    pub error: TransitionImpossibleError<M, I>,
+}
+
+impl<M: Debug, I: Debug> fmt::Display for ReplayError<M, I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "input #{} of the log: {}", self.index, self.error)
+    }
+}
+
+impl<M: Debug + 'static, I: Debug + 'static> Error for ReplayError<M, I> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Rebuilds the state of a machine by consuming the inputs of a log one by one,
+/// starting from the `initial` state. Stops at the first input the machine
+/// cannot consume.
+///
+/// ```rust
+/// use rust_fsm::*;
+///
+/// state_machine! {
+///     #[derive(Debug, PartialEq)]
+///     Door(Closed) => #[derive(Debug)] Action => #[derive(Debug, PartialEq)] Effect
+///
+///     Closed => Open => Opened [Creak],
+///     Opened => Close => Closed,
+/// }
+///
+/// let replayed = replay(Door::Closed, [Action::Open, Action::Close, Action::Open]).unwrap();
+/// assert_eq!(replayed.state, Door::Opened);
+/// assert_eq!(replayed.outputs, [Effect::Creak, Effect::Creak]);
+///
+/// let error = replay(Door::Closed, [Action::Open, Action::Open]).unwrap_err();
+/// assert_eq!(error.index, 1);
+/// assert_eq!(error.error.state, Door::Opened);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn replay<'i, M, I>(
+    initial: M,
+    inputs: I,
+) -> Result<Replay<M, M::Input<'i>, M::Output<'i>>, ReplayError<M, M::Input<'i>>>
+where
+    M: StateMachine,
+    I: IntoIterator<Item = M::Input<'i>>,
+{
+    let mut state = initial;
+    let mut outputs = Vec::new();
+    for (index, input) in inputs.into_iter().enumerate() {
+        match state.transition(input) {
+            Ok((next, produced)) => {
+                state = next;
+                outputs.extend(produced);
+            }
+            Err(error) => return Err(ReplayError { index, error }),
+        }
+    }
+    Ok(Replay {
+        state,
+        outputs,
+        rejected: Vec::new(),
+    })
+}
+
+/// Rebuilds the state of a machine like [`replay`], but skips the inputs the
+/// machine cannot consume and collects them instead.
+///
+/// ```rust
+/// use rust_fsm::*;
+///
+/// state_machine! {
+///     #[derive(Debug, PartialEq)]
+///     Door(Closed) => #[derive(Debug, PartialEq)] Action => Effect
+///
+///     Closed => Open => Opened,
+///     Opened => Close => Closed,
+/// }
+///
+/// let replayed = replay_lenient(Door::Closed, [Action::Open, Action::Open, Action::Close]);
+/// assert_eq!(replayed.state, Door::Closed);
+/// assert_eq!(replayed.rejected, [(1, Action::Open)]);
+/// ```
+pub fn replay_lenient<'i, M, I>(initial: M, inputs: I) -> Replay<M, M::Input<'i>, M::Output<'i>>
+where
+    M: StateMachine,
+    I: IntoIterator<Item = M::Input<'i>>,
+{
+    let mut state = initial;
+    let mut outputs = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, input) in inputs.into_iter().enumerate() {
+        state = match state.transition(input) {
+            Ok((next, produced)) => {
+                outputs.extend(produced);
+                next
+            }
+            Err(TransitionImpossibleError { state, input }) => {
+                rejected.push((index, input));
+                state
+            }
+        };
+    }
+    Replay {
+        state,
+        outputs,
+        rejected,
+    }
+}
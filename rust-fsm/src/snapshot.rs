@@ -0,0 +1,314 @@
+use core::{fmt, marker::PhantomData};
+use std::error::Error;
+
+use serde::{
+    de::{
+        self, DeserializeSeed, EnumAccess, IgnoredAny, IntoDeserializer, MapAccess, SeqAccess,
+        Visitor,
+    },
+    forward_to_deserialize_any, Deserialize, Deserializer, Serialize,
+};
+
+use crate::StateMachineMeta;
+
+/// The state of a machine along with the hash of its definition, to be stored
+/// and restored after the definition has possibly changed. Requires the
+/// `serde` and `std` features.
+///
+/// When the stored hash differs from [`StateMachineMeta::DEFINITION_HASH`],
+/// [`Snapshot::restore`] passes the name of the stored state to a migration
+/// hook, which can map the states that were renamed to their new names.
+///
+/// ```rust
+/// use rust_fsm::*;
+///
+/// state_machine! {
+///     #[fsm(serde)]
+///     #[derive(Debug, PartialEq)]
+///     Door(Closed) => Action => Effect
+///
+///     Closed => Open => Opened,
+///     Opened => Close => Closed,
+/// }
+///
+/// let stored = serde_json::to_string(&Snapshot::new(&Door::Opened)).unwrap();
+/// let mut deserializer = serde_json::Deserializer::from_str(&stored);
+/// let door = Snapshot::<Door>::restore(&mut deserializer, |_| None).unwrap();
+/// assert_eq!(door, Door::Opened);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot<S> {
+    /// The hash of the definition of the machine the state belongs to.
+    pub definition_hash: u64,
+    pub state: S,
+}
+
+impl<'a, M: StateMachineMeta> Snapshot<&'a M> {
+    /// Takes a snapshot of the machine in its current state.
+    pub fn new(state: &'a M) -> Self {
+        Self {
+            definition_hash: M::DEFINITION_HASH,
+            state,
+        }
+    }
+}
+
+impl<M: StateMachineMeta> Snapshot<M> {
+    /// Restores the machine from a snapshot. If the definition of the machine
+    /// has changed since the snapshot was taken, the name of the stored state
+    /// is passed to `migrate`, which returns the new name of the state or
+    /// `None` if it is still the same. Renaming the states requires a
+    /// self-describing format.
+    pub fn restore<'de, D, F>(deserializer: D, mut migrate: F) -> Result<M, RestoreError<D::Error>>
+    where
+        D: Deserializer<'de>,
+        M: Deserialize<'de>,
+        F: FnMut(&str) -> Option<&'static str>,
+    {
+        let mut unknown = None;
+        let seed = SnapshotSeed {
+            renaming: Renaming {
+                states: M::STATES,
+                migrate: &mut migrate,
+                unknown: &mut unknown,
+            },
+            machine: PhantomData::<M>,
+        };
+        seed.deserialize(deserializer)
+            .map_err(|error| match unknown {
+                Some(state) => RestoreError::UnknownState(state),
+                None => RestoreError::Deserialize(error),
+            })
+    }
+}
+
+/// An error restoring a machine from a [`Snapshot`].
+#[derive(Debug)]
+pub enum RestoreError<E> {
+    /// The stored state does not exist in the machine, even after the
+    /// migration.
+    UnknownState(String),
+    /// The snapshot could not be deserialized.
+    Deserialize(E),
+}
+
+impl<E: fmt::Display> fmt::Display for RestoreError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownState(state) => write!(f, "unknown state `{state}`"),
+            Self::Deserialize(error) => write!(f, "cannot deserialize the snapshot: {error}"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for RestoreError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::UnknownState(_) => None,
+            Self::Deserialize(error) => Some(error),
+        }
+    }
+}
+
+/// Renames the variant of the stored state.
+struct Renaming<'a, F> {
+    states: &'static [&'static str],
+    migrate: &'a mut F,
+    /// The name of the stored state if it does not exist.
+    unknown: &'a mut Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum Field {
+    DefinitionHash,
+    State,
+    #[serde(other)]
+    Other,
+}
+
+struct SnapshotSeed<'a, M, F> {
+    renaming: Renaming<'a, F>,
+    machine: PhantomData<M>,
+}
+
+impl<'de, M, F> DeserializeSeed<'de> for SnapshotSeed<'_, M, F>
+where
+    M: StateMachineMeta + Deserialize<'de>,
+    F: FnMut(&str) -> Option<&'static str>,
+{
+    type Value = M;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<M, D::Error> {
+        deserializer.deserialize_struct("Snapshot", &["definition_hash", "state"], self)
+    }
+}
+
+impl<'a, 'de, M, F> SnapshotSeed<'a, M, F>
+where
+    M: StateMachineMeta + Deserialize<'de>,
+    F: FnMut(&str) -> Option<&'static str>,
+{
+    /// The state is deserialized as is if the definition is the same.
+    fn state_seed(self, definition_hash: u64) -> StateSeed<'a, M, F> {
+        StateSeed {
+            renaming: (definition_hash != M::DEFINITION_HASH).then_some(self.renaming),
+            machine: PhantomData,
+        }
+    }
+}
+
+impl<'de, M, F> Visitor<'de> for SnapshotSeed<'_, M, F>
+where
+    M: StateMachineMeta + Deserialize<'de>,
+    F: FnMut(&str) -> Option<&'static str>,
+{
+    type Value = M;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a snapshot of a state machine")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<M, A::Error> {
+        let definition_hash = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        seq.next_element_seed(self.state_seed(definition_hash))?
+            .ok_or_else(|| de::Error::invalid_length(1, &"a snapshot of a state machine"))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<M, A::Error> {
+        let mut definition_hash = None;
+        let mut seed = Some(self);
+        let mut state = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::DefinitionHash => definition_hash = Some(map.next_value()?),
+                Field::State => {
+                    let definition_hash = definition_hash.ok_or_else(|| {
+                        de::Error::custom("`definition_hash` must precede `state`")
+                    })?;
+                    let seed = seed
+                        .take()
+                        .ok_or_else(|| de::Error::duplicate_field("state"))?;
+                    state = Some(map.next_value_seed(seed.state_seed(definition_hash))?);
+                }
+                Field::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        state.ok_or_else(|| de::Error::missing_field("state"))
+    }
+}
+
+struct StateSeed<'a, M, F> {
+    renaming: Option<Renaming<'a, F>>,
+    machine: PhantomData<M>,
+}
+
+impl<'de, M, F> DeserializeSeed<'de> for StateSeed<'_, M, F>
+where
+    M: Deserialize<'de>,
+    F: FnMut(&str) -> Option<&'static str>,
+{
+    type Value = M;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<M, D::Error> {
+        match self.renaming {
+            Some(renaming) => M::deserialize(Renamed {
+                deserializer,
+                renaming,
+            }),
+            None => M::deserialize(deserializer),
+        }
+    }
+}
+
+/// A deserializer renaming the variant of the enum deserialized from it.
+struct Renamed<'a, D, F> {
+    deserializer: D,
+    renaming: Renaming<'a, F>,
+}
+
+impl<'de, D, F> Deserializer<'de> for Renamed<'_, D, F>
+where
+    D: Deserializer<'de>,
+    F: FnMut(&str) -> Option<&'static str>,
+{
+    type Error = D::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.deserializer.deserialize_any(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.deserializer.deserialize_enum(
+            name,
+            variants,
+            Renamed {
+                deserializer: visitor,
+                renaming: self.renaming,
+            },
+        )
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de, V, F> Visitor<'de> for Renamed<'_, V, F>
+where
+    V: Visitor<'de>,
+    F: FnMut(&str) -> Option<&'static str>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.deserializer.expecting(f)
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        self.deserializer.visit_enum(Renamed {
+            deserializer: data,
+            renaming: self.renaming,
+        })
+    }
+}
+
+impl<'de, A, F> EnumAccess<'de> for Renamed<'_, A, F>
+where
+    A: EnumAccess<'de>,
+    F: FnMut(&str) -> Option<&'static str>,
+{
+    type Error = A::Error;
+    type Variant = A::Variant;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, A::Variant), A::Error> {
+        let Renaming {
+            states,
+            migrate,
+            unknown,
+        } = self.renaming;
+        let (stored, variant) = self.deserializer.variant_seed(PhantomData::<String>)?;
+        let name = migrate(&stored).unwrap_or(&stored);
+        if !states.contains(&name) {
+            let error = de::Error::custom(format_args!("unknown state `{stored}`"));
+            *unknown = Some(stored);
+            return Err(error);
+        }
+        let name = seed.deserialize(IntoDeserializer::<A::Error>::into_deserializer(name))?;
+        Ok((name, variant))
+    }
+}
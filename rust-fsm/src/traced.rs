@@ -0,0 +1,112 @@
+use core::fmt::{self, Debug};
+
+use crate::{OutputsRef, StateMachine, StateMachineMeta, TransitionImpossibleError};
+
+/// The names of the outputs of a transition.
+struct OutputNames<'a, T, O>(&'a O, fn(&T) -> &'static str);
+
+impl<T, O: OutputsRef<T>> Debug for OutputNames<'_, T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter_outputs().map(self.1))
+            .finish()
+    }
+}
+
+/// A state machine emitting a [`tracing`] event for every transition it
+/// performs, and a warning for every input it cannot consume. The events carry
+/// the name of the machine and the names of the states, the input and the
+/// outputs. Requires the `tracing` feature.
+///
+/// ```rust
+/// use rust_fsm::*;
+///
+/// state_machine! {
+///     #[derive(Debug)]
+///     Door(Closed) => #[derive(Debug)] Action => Effect
+///
+///     Closed => Open => Opened,
+///     Opened => Close => Closed,
+/// }
+///
+/// let mut door = Traced::with_name(Door::Closed, "garage");
+/// door.consume(Action::Open).unwrap();
+/// assert!(door.consume(Action::Open).is_err());
+/// ```
+pub struct Traced<M> {
+    machine: M,
+    name: &'static str,
+}
+
+impl<M> Traced<M> {
+    /// Traces the transitions of the machine, naming it after its type.
+    pub fn new(machine: M) -> Self {
+        Self::with_name(machine, core::any::type_name::<M>())
+    }
+
+    /// Traces the transitions of the machine under the provided name.
+    pub fn with_name(machine: M, name: &'static str) -> Self {
+        Self { machine, name }
+    }
+
+    /// The current state of the machine.
+    pub fn state(&self) -> &M {
+        &self.machine
+    }
+
+    /// Stops tracing the machine and gives it back.
+    pub fn into_inner(self) -> M {
+        self.machine
+    }
+}
+
+impl<M: Debug> Debug for Traced<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Traced")
+            .field("machine", &self.machine)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl<M> StateMachine for Traced<M>
+where
+    M: StateMachineMeta,
+    for<'i> M::Outputs<'i>: OutputsRef<M::Output<'i>>,
+{
+    type Input<'i> = M::Input<'i>;
+    type Output<'i> = M::Output<'i>;
+    type Outputs<'i> = M::Outputs<'i>;
+
+    fn transition<'i>(
+        mut self,
+        input: Self::Input<'i>,
+    ) -> Result<(Self, Self::Outputs<'i>), TransitionImpossibleError<Self, Self::Input<'i>>> {
+        let from = self.machine.state_name();
+        let input_name = M::input_name(&input);
+        match self.machine.transition(input) {
+            Ok((machine, outputs)) => {
+                tracing::info!(
+                    machine = self.name,
+                    from,
+                    input = input_name,
+                    to = machine.state_name(),
+                    outputs = ?OutputNames(&outputs, M::output_name),
+                    "state transition",
+                );
+                self.machine = machine;
+                Ok((self, outputs))
+            }
+            Err(TransitionImpossibleError { state, input }) => {
+                tracing::warn!(
+                    machine = self.name,
+                    state = from,
+                    input = input_name,
+                    "state transition impossible",
+                );
+                self.machine = state;
+                Err(TransitionImpossibleError { state: self, input })
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug, PartialEq)]
+    CircuitBreaker(Closed) => #[derive(Debug)] pub Result => #[derive(Debug, PartialEq)] pub Action
+
+    Closed => Unsuccessful => Open [NotifyOps],
+    Open => {
+        TimerTriggered => HalfOpen,
+        Unsuccessful => _,
+    },
+    HalfOpen => {
+        Successful => Closed,
+        Unsuccessful => Open
+    },
+    enter Open [StartTimer],
+    exit Open [CancelTimer],
+}
+
+#[test]
+fn entry_and_exit_actions() {
+    let mut machine = CircuitBreaker::default();
+
+    let outputs = machine.consume(Result::Unsuccessful).unwrap();
+    assert_eq!(outputs.len(), 2);
+    assert!(outputs
+        .into_iter()
+        .eq([Action::NotifyOps, Action::StartTimer]));
+
+    // Staying in the same state does not trigger the actions
+    let outputs = machine.consume(Result::Unsuccessful).unwrap();
+    assert!(outputs.is_empty());
+
+    let outputs = machine.consume(Result::TimerTriggered).unwrap();
+    assert!(outputs.into_iter().eq([Action::CancelTimer]));
+    assert_eq!(machine, CircuitBreaker::HalfOpen);
+
+    let (machine, outputs) = machine.transition(Result::Unsuccessful).unwrap();
+    assert_eq!(machine, CircuitBreaker::Open);
+    assert!(outputs.iter().eq(&[Action::StartTimer]));
+}
@@ -41,7 +41,7 @@ fn circit_breaker_dsl() {
         std::thread::sleep(Duration::new(5, 0));
         let mut lock = machine_wait.lock().unwrap();
         let res = lock.consume(Result::TimerTriggered).unwrap();
-        assert!(matches!(res, None));
+        assert!(res.is_none());
         assert!(matches!(*lock, CircuitBreaker::HalfOpen));
     });
 
@@ -51,7 +51,7 @@ fn circit_breaker_dsl() {
         std::thread::sleep(Duration::new(1, 0));
         let mut lock = machine_try.lock().unwrap();
         let res = lock.consume(Result::Successful);
-        assert!(matches!(res, Err(_)));
+        assert!(res.is_err());
         assert!(matches!(*lock, CircuitBreaker::Open));
     });
 
@@ -60,7 +60,7 @@ fn circit_breaker_dsl() {
     {
         let mut lock = machine.lock().unwrap();
         let res = lock.consume(Result::Successful).unwrap();
-        assert!(matches!(res, None));
+        assert!(res.is_none());
         assert!(matches!(*lock, CircuitBreaker::Closed));
     }
 }
@@ -54,7 +54,7 @@ fn circit_breaker_dsl() {
         std::thread::sleep(Duration::new(5, 0));
         let mut lock = machine_wait.lock().unwrap();
         let res = lock.consume(Input::TimerTriggered).unwrap();
-        assert!(matches!(res, None));
+        assert!(res.is_none());
         assert!(matches!(*lock, State::HalfOpen));
     });
 
@@ -64,7 +64,7 @@ fn circit_breaker_dsl() {
         std::thread::sleep(Duration::new(1, 0));
         let mut lock = machine_try.lock().unwrap();
         let res = lock.consume(Input::Successful);
-        assert!(matches!(res, Err(_)));
+        assert!(res.is_err());
         assert!(matches!(*lock, State::Open));
     });
 
@@ -73,7 +73,7 @@ fn circit_breaker_dsl() {
     {
         let mut lock = machine.lock().unwrap();
         let res = lock.consume(Input::Successful).unwrap();
-        assert!(matches!(res, None));
+        assert!(res.is_none());
         assert!(matches!(*lock, State::Closed));
     }
 }
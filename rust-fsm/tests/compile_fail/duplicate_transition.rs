@@ -0,0 +1,17 @@
+use rust_fsm::*;
+
+state_machine! {
+    Door(Closed) => Action => Effect
+
+    Closed => {
+        Kick(u8 => n) => Broken,
+        Kick(m) => Opened,
+    },
+    Opened => {
+        Close => Closed,
+        Close => Opened,
+    },
+    final Broken,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use rust_fsm::*;
+
+state_machine! {
+    Door(Open) => Action => Effect
+
+    Open => Break => Broken,
+    Broken => Repair => Open,
+    final Broken,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![deny(deprecated)]
+
+use rust_fsm::*;
+
+state_machine! {
+    Door(Closed) => Action => Effect
+
+    Closed => {
+        Kick(u8 => _) => Broken,
+        Kick(n if n > 3) => Opened,
+    },
+    Opened => Close => Closed,
+    final Broken,
+}
+
+fn main() {}
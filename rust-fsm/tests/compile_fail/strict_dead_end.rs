@@ -0,0 +1,14 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[fsm(strict)]
+    Door(Closed) => Action => Effect
+
+    Closed => {
+        Open => Opened,
+        Break => Broken,
+    },
+    Opened => Close => Closed,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[fsm(strict)]
+    Door(Closed) => Action => Effect
+
+    Closed => Open => Opened,
+    Opened => Close => Closed,
+    Locked => Unlock => Closed,
+    Jammed => Kick => Jammed,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[fsm(total)]
+    Switch(Off) => Action => Effect
+
+    Off => {
+        Toggle => On,
+        Reset(u8 => level if level > 0) => _,
+    },
+    On => Toggle => Off,
+    Broken => {
+        Toggle => Broken,
+        Repair => Off,
+    },
+}
+
+fn main() {}
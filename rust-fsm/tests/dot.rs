@@ -0,0 +1,80 @@
+#![cfg(feature = "dot")]
+
+use rust_fsm::*;
+
+state_machine! {
+    pub Connection(Disconnected) => pub Event => pub Effect
+
+    Disconnected => Connect => Connected,
+    Connected => Disconnect => Disconnected [Goodbye],
+    Idle => Request(u32 => id if id > 0) => Busy,
+    Busy => Done => Idle,
+    Busy => Poll => _,
+    _ except Connected => Fail => Broken,
+    enter Busy [Lock],
+    exit Busy [Unlock],
+    state Connected { Idle, Busy },
+    final Broken,
+}
+
+#[test]
+fn dot() {
+    assert_eq!(
+        Connection::DOT,
+        r#"digraph "Connection" {
+    compound=true;
+    node [shape=box, style=rounded];
+    "[*]" [shape=point];
+    "[*]" -> "Disconnected";
+    "Broken" [label="Broken", peripheries=2];
+    "Disconnected" [label="Disconnected"];
+    subgraph "cluster_Connected" {
+        label="Connected";
+        "Idle" [label="Idle"];
+        "Busy" [label="Busy\nenter / Lock\nexit / Unlock"];
+    }
+    "Disconnected" -> "Idle" [label="Connect", lhead="cluster_Connected"];
+    "Idle" -> "Disconnected" [label="Disconnect / Goodbye", ltail="cluster_Connected"];
+    "Idle" -> "Busy" [label="Request(id) if id > 0"];
+    "Busy" -> "Idle" [label="Done"];
+    "Busy" -> "Busy" [label="Poll"];
+    "Disconnected" -> "Broken" [label="Fail"];
+}"#
+    );
+}
+
+pub mod keywords {
+    use rust_fsm::*;
+
+    // The names of the machine and the states are keywords of the DOT language.
+    state_machine! {
+        pub Strict(Node) => pub Input => pub Output
+
+        Node => Next => Edge,
+        Edge => Next => Graph,
+        Subgraph => Next => Node,
+        state Subgraph { Graph },
+    }
+}
+
+#[test]
+fn keywords() {
+    assert_eq!(
+        keywords::Strict::DOT,
+        r#"digraph "Strict" {
+    compound=true;
+    node [shape=box, style=rounded];
+    "[*]" [shape=point];
+    "[*]" -> "Node";
+    "Edge" [label="Edge"];
+    "Node" [label="Node"];
+    subgraph "cluster_Subgraph" {
+        label="Subgraph";
+        "Graph" [label="Graph"];
+    }
+    "Node" -> "Edge" [label="Next"];
+    "Edge" -> "Graph" [label="Next"];
+    "Graph" -> "Node" [label="Next", ltail="cluster_Subgraph"];
+}"#
+    );
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "async")]
+
+use futures::{channel::mpsc, executor::block_on, join, SinkExt, StreamExt};
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug, Clone, PartialEq)]
+    pub CircuitBreaker(Closed) => #[derive(Debug, PartialEq)] pub Result => #[derive(Debug, PartialEq)] pub Action
+
+    Closed => Unsuccessful => Open [SetupTimer, NotifyOps],
+    Open => TimerTriggered => HalfOpen,
+    HalfOpen => {
+        Successful => Closed,
+        Unsuccessful => Open [SetupTimer],
+    },
+}
+
+#[test]
+fn run() {
+    let (mut inputs, input_stream) = mpsc::channel(1);
+    let (output_sink, outputs) = mpsc::unbounded();
+    let driver = Driver::new(CircuitBreaker::initial(), input_stream, output_sink);
+    let mut watch = driver.watch();
+    assert_eq!(watch.get(), CircuitBreaker::Closed);
+
+    let (state, states, ()) = block_on(async {
+        join!(
+            driver.run(),
+            async {
+                let mut states = vec![];
+                while let Some(state) = watch.changed().await {
+                    states.push(state);
+                }
+                states
+            },
+            async {
+                for input in [
+                    Result::Unsuccessful,
+                    Result::TimerTriggered,
+                    Result::Successful,
+                    Result::Unsuccessful,
+                ] {
+                    inputs.send(input).await.unwrap();
+                }
+                inputs.close_channel();
+            },
+        )
+    });
+    assert_eq!(state.unwrap(), CircuitBreaker::Open);
+    // The intermediate states may be skipped if the watch is not fast enough
+    assert_eq!(states.last(), Some(&CircuitBreaker::Open));
+    assert_eq!(
+        block_on(outputs.collect::<Vec<_>>()),
+        [
+            Action::SetupTimer,
+            Action::NotifyOps,
+            Action::SetupTimer,
+            Action::NotifyOps
+        ]
+    );
+}
+
+#[test]
+fn transition_impossible() {
+    let inputs = futures::stream::iter([Result::Unsuccessful, Result::Successful]);
+    let (output_sink, _outputs) = mpsc::unbounded();
+    let driver = Driver::new(CircuitBreaker::initial(), inputs, output_sink);
+    let mut watch = driver.watch();
+
+    let Err(DriverError::TransitionImpossible(error)) = block_on(driver.run()) else {
+        panic!("the input must be rejected");
+    };
+    assert_eq!(error.state, CircuitBreaker::Open);
+    assert_eq!(error.input, Result::Successful);
+    assert_eq!(block_on(watch.changed()), Some(CircuitBreaker::Open));
+    assert_eq!(block_on(watch.changed()), None);
+}
+
+#[test]
+fn sink_closed() {
+    let inputs = futures::stream::iter([Result::Unsuccessful]);
+    let (output_sink, outputs) = mpsc::unbounded();
+    drop(outputs);
+    let driver = Driver::new(CircuitBreaker::initial(), inputs, output_sink);
+    let error = block_on(driver.run()).unwrap_err();
+    assert!(error.to_string().starts_with("cannot send the outputs: "));
+    let DriverError::Sink { machine, .. } = error else {
+        panic!("the outputs must not be sent");
+    };
+    // The transition has been performed regardless
+    assert_eq!(machine, CircuitBreaker::Open);
+}
@@ -0,0 +1,48 @@
+use rust_fsm::*;
+
+state_machine_from_file!(
+    "tests/data/door.mmd",
+    #[derive(Debug, PartialEq)]
+    pub Door => #[derive(Debug)] pub Action => #[derive(Debug, PartialEq)] pub Effect
+);
+
+// The names are taken from the document
+state_machine_from_file!("tests/data/turnstile.scxml");
+
+#[test]
+fn mermaid() {
+    let mut machine = Door::default();
+    assert_eq!(machine, Door::Unlocked);
+    assert_eq!(machine.parent(), Some("Closed"));
+
+    machine.consume(Action::Lock).unwrap();
+    assert!(machine.consume(Action::Open).is_err());
+    machine.consume(Action::Unlock).unwrap();
+
+    let outputs = machine.consume(Action::Open).unwrap();
+    assert!(outputs.into_iter().eq([Effect::Beep, Effect::LightOn]));
+    let outputs = machine.consume(Action::Close).unwrap();
+    assert!(outputs.into_iter().eq([Effect::LightOff]));
+    assert_eq!(machine, Door::Unlocked);
+
+    let outputs = machine.consume(Action::Break).unwrap();
+    assert!(outputs.into_iter().eq([Effect::Alarm]));
+    assert!(machine.is_final());
+}
+
+#[test]
+fn scxml() {
+    let mut machine = Turnstile::default();
+    assert!(matches!(machine, Turnstile::Locked));
+
+    let outputs = machine.consume(TurnstileInput::Coin);
+    assert!(matches!(outputs, Ok(Some(TurnstileOutput::Release))));
+    let outputs = machine.consume(TurnstileInput::Coin);
+    assert!(matches!(outputs, Ok(Some(TurnstileOutput::Refund))));
+    assert!(machine.consume(TurnstileInput::Push).is_ok());
+    assert!(matches!(machine, Turnstile::Locked));
+
+    let outputs = machine.consume(TurnstileInput::Break);
+    assert!(matches!(outputs, Ok(Some(TurnstileOutput::CallService))));
+    assert!(machine.is_final());
+}
@@ -0,0 +1,68 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[fsm(strict)]
+    #[derive(Debug, PartialEq)]
+    Connection(Disconnected) => #[derive(Debug)] pub Event => #[derive(Debug, PartialEq)] pub Effect
+
+    Disconnected => Connect => Connected,
+    Connected => Disconnect => Disconnected,
+    Idle => {
+        Read => Reading,
+        Write => Writing(u32 => 0),
+    },
+    Reading => Done => Idle,
+    Writing(n) => Progress => Writing(n + 1),
+    Busy => {
+        Ping => _ [Pong],
+        Shutdown => Draining,
+    },
+    Draining => {
+        Done => Disconnected,
+        Disconnect => _ [Refused],
+    },
+    state Connected { Idle, Busy { Reading, Writing }, Draining },
+    enter Connected [Greet],
+    exit Connected [Goodbye],
+    exit Busy [Flush],
+}
+
+#[test]
+fn superstate_transitions_are_inherited() {
+    let mut machine = Connection::default();
+    let outputs = machine.consume(Event::Connect).unwrap();
+    // Entering a superstate enters its first state
+    assert_eq!(machine, Connection::Idle);
+    assert!(outputs.into_iter().eq([Effect::Greet]));
+    assert_eq!(machine.parent(), Some("Connected"));
+
+    machine.consume(Event::Write).unwrap();
+    machine.consume(Event::Progress).unwrap();
+    assert_eq!(machine, Connection::Writing(1));
+    assert_eq!(machine.parent(), Some("Busy"));
+
+    // Inherited from `Busy`
+    let outputs = machine.consume(Event::Ping).unwrap();
+    assert!(outputs.into_iter().eq([Effect::Pong]));
+    assert_eq!(machine, Connection::Writing(1));
+
+    // Inherited from `Connected`
+    let outputs = machine.consume(Event::Disconnect).unwrap();
+    assert!(outputs.into_iter().eq([Effect::Flush, Effect::Goodbye]));
+    assert_eq!(machine, Connection::Disconnected);
+    assert_eq!(machine.parent(), None);
+}
+
+#[test]
+fn state_transitions_take_precedence() {
+    let mut machine = Connection::Draining;
+    let outputs = machine.consume(Event::Disconnect).unwrap();
+    assert!(outputs.into_iter().eq([Effect::Refused]));
+    assert_eq!(machine, Connection::Draining);
+
+    // Moving within a superstate does not leave it
+    let mut machine = Connection::Reading;
+    let outputs = machine.consume(Event::Shutdown).unwrap();
+    assert!(outputs.into_iter().eq([Effect::Flush]));
+    assert_eq!(machine, Connection::Draining);
+}
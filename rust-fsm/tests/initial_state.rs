@@ -0,0 +1,40 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug, PartialEq)]
+    CircuitBreaker(Closed) => #[derive(Debug)] pub Result => #[derive(Debug)] pub Action
+
+    Closed => Unsuccessful => Open [SetupTimer],
+    Open => TimerTriggered => HalfOpen,
+    HalfOpen => {
+        Successful => Closed,
+        Unsuccessful => Open [SetupTimer]
+    }
+}
+
+state_machine! {
+    #[derive(Debug, PartialEq)]
+    Counter(Counting(u8 => 0)) => #[derive(Debug)] pub Tick => __
+
+    Counting(n) => Increment => Counting(n + 1),
+    Counting(_) => Reset => Counting(0),
+}
+
+#[test]
+fn initial_state() {
+    assert_eq!(CircuitBreaker::initial(), CircuitBreaker::Closed);
+    assert_eq!(CircuitBreaker::default(), CircuitBreaker::Closed);
+
+    let mut machine = CircuitBreaker::default();
+    machine.consume(Result::Unsuccessful).unwrap();
+    assert_eq!(machine, CircuitBreaker::Open);
+}
+
+#[test]
+fn initial_state_with_data() {
+    let mut machine = Counter::default();
+    assert_eq!(machine, Counter::Counting(0));
+    machine.consume(Tick::Increment).unwrap();
+    assert_eq!(machine, Counter::Counting(1));
+    assert_eq!(machine.name(), "Counting");
+}
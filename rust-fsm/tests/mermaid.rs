@@ -0,0 +1,89 @@
+use rust_fsm::*;
+
+state_machine! {
+    pub Connection(Disconnected) => pub Event => pub Effect
+
+    Disconnected => Connect => Connected,
+    Connected => Disconnect => Disconnected [Goodbye],
+    Idle => Request => Busy,
+    Busy => Done => Idle,
+    enter Busy [Lock],
+    state Connected { Idle, Busy },
+    Disconnected => Fail => Broken,
+    final Broken,
+}
+
+#[test]
+fn mermaid() {
+    assert_eq!(
+        Connection::MERMAID,
+        r#"stateDiagram-v2
+    [*] --> Disconnected
+    state Connected {
+        [*] --> Idle
+        Idle
+        Busy
+    }
+    Disconnected --> Connected: Connect
+    Connected --> Disconnected: Disconnect / Goodbye
+    Idle --> Busy: Request
+    Busy --> Idle: Done
+    Disconnected --> Broken: Fail
+    Busy: enter / Lock
+    Broken --> [*]"#
+    );
+}
+
+state_machine! {
+    pub Valve(Closed) => pub Command => pub Report
+
+    Closed => Open(u32 => n if n > 3) => Opened(u32 => n) [DefaultReport],
+    Opened(_) => Close => Closed,
+    Opened(n) => Hold => _,
+    Closed => Default => Closed,
+}
+
+#[test]
+fn escaping() {
+    assert_eq!(
+        Valve::MERMAID,
+        r#"stateDiagram-v2
+    [*] --> Closed
+    Closed --> Opened: Open#40;n#41; #91;n #62; 3#93; / DefaultReport
+    Opened --> Closed: Close
+    Opened --> Opened: Hold
+    Closed --> Closed: Default
+    note left of Opened: u32"#
+    );
+}
+
+#[test]
+fn current_state() {
+    let machine = Connection::Busy;
+    assert_eq!(machine.state_name(), "Busy");
+    assert_eq!(
+        machine.render_mermaid_with_current(None),
+        format!(
+            "{}
+    classDef current stroke:#e4572e,stroke-width:3px,fill:#f6c9bd
+    class Busy current",
+            Connection::MERMAID
+        )
+    );
+
+    let last_transition = Connection::TRANSITIONS
+        .iter()
+        .find(|x| x.source == "Idle" && x.input == "Request");
+    assert_eq!(
+        machine.render_mermaid_with_current(last_transition),
+        format!(
+            "{}
+    classDef current stroke:#e4572e,stroke-width:3px,fill:#f6c9bd
+    class Busy current
+    classDef previous stroke:#e4572e,stroke-dasharray:4 4
+    class Idle previous
+    note right of Busy: last input: Request",
+            Connection::MERMAID
+        )
+    );
+}
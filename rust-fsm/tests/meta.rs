@@ -0,0 +1,52 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug)]
+    pub Door(Closed) => #[derive(Debug)] pub Action => #[derive(Debug)] pub Effect
+
+    Closed => {
+        Open => Opened [Creak],
+        Lock(u8 => code if code > 0) => Locked,
+    },
+    Opened => {
+        Close => Closed,
+        Open => _,
+    },
+    Locked => Unlock => Closed,
+    exit Locked [Click],
+    state Shut { Closed, Locked },
+    Shut => Break => Broken,
+    final Broken,
+}
+
+#[test]
+fn names() {
+    assert_eq!(Door::STATES, ["Broken", "Closed", "Locked", "Opened"]);
+    assert_eq!(Door::INPUTS, ["Break", "Close", "Lock", "Open", "Unlock"]);
+    assert_eq!(Door::OUTPUTS, ["Click", "Creak"]);
+    assert_eq!(Door::INITIAL_STATE, Some("Closed"));
+    assert_eq!(Door::FINAL_STATES, ["Broken"]);
+}
+
+#[test]
+fn transitions() {
+    let transition = |source, input, target, outputs, guarded| TransitionInfo {
+        source,
+        input,
+        target,
+        outputs,
+        guarded,
+    };
+    assert_eq!(
+        Door::transition_table(),
+        [
+            transition("Closed", "Open", "Opened", &["Creak"], false),
+            transition("Closed", "Lock", "Locked", &[], true),
+            transition("Opened", "Close", "Closed", &[], false),
+            transition("Opened", "Open", "Opened", &[], false),
+            transition("Locked", "Unlock", "Closed", &["Click"], false),
+            transition("Closed", "Break", "Broken", &[], false),
+            transition("Locked", "Break", "Broken", &["Click"], false),
+        ]
+    );
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "metrics")]
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    thread::sleep,
+    time::Duration,
+};
+
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, Key, KeyName, Metadata, Recorder, SharedString,
+    Unit,
+};
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug)]
+    pub CircuitBreaker(Closed) => #[derive(Debug)] pub Result => #[derive(Debug)] pub Action
+
+    Closed => Unsuccessful => Open [SetupTimer],
+    Open => TimerTriggered => HalfOpen,
+    HalfOpen => {
+        Successful => Closed,
+        Unsuccessful => Open [SetupTimer],
+    },
+}
+
+/// A counter or a gauge.
+#[derive(Default)]
+struct Value(Mutex<f64>);
+
+impl CounterFn for Value {
+    fn increment(&self, value: u64) {
+        *self.0.lock().unwrap() += value as f64;
+    }
+    fn absolute(&self, value: u64) {
+        *self.0.lock().unwrap() = value as f64;
+    }
+}
+
+impl GaugeFn for Value {
+    fn increment(&self, value: f64) {
+        *self.0.lock().unwrap() += value;
+    }
+    fn decrement(&self, value: f64) {
+        *self.0.lock().unwrap() -= value;
+    }
+    fn set(&self, value: f64) {
+        *self.0.lock().unwrap() = value;
+    }
+}
+
+/// Collects the metrics keyed by `name{label=value,...}`.
+#[derive(Default)]
+struct Metrics(Mutex<BTreeMap<String, Arc<Value>>>);
+
+impl Metrics {
+    fn value(&self, key: &Key) -> Arc<Value> {
+        let labels = key
+            .labels()
+            .map(|label| format!("{}={}", label.key(), label.value()))
+            .collect::<Vec<_>>();
+        let key = format!("{}{{{}}}", key.name(), labels.join(","));
+        self.0.lock().unwrap().entry(key).or_default().clone()
+    }
+
+    fn get(&self, key: &str) -> Option<f64> {
+        let values = self.0.lock().unwrap();
+        values.get(key).map(|value| *value.0.lock().unwrap())
+    }
+}
+
+impl Recorder for Metrics {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.value(key))
+    }
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(self.value(key))
+    }
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+#[test]
+fn metrics() {
+    let metrics = Metrics::default();
+    let seconds = |state: &str| {
+        metrics.get(&format!(
+            "rust_fsm_state_seconds{{machine=backend,state={state}}}"
+        ))
+    };
+    metrics::with_local_recorder(&metrics, || {
+        let mut machine = Metered::with_name(CircuitBreaker::Closed, "backend");
+        sleep(Duration::from_millis(20));
+        machine.consume(Result::Unsuccessful).unwrap();
+        // Leaving a state adds the time spent in it to that state only
+        let closed = seconds("Closed").unwrap();
+        assert!(closed >= 0.02);
+        assert_eq!(seconds("Open"), None);
+
+        // Rejected inputs do not leave the state
+        assert!(machine.consume(Result::Successful).is_err());
+        assert!(machine.consume(Result::Successful).is_err());
+        assert_eq!(seconds("Open"), None);
+
+        sleep(Duration::from_millis(10));
+        machine.consume(Result::TimerTriggered).unwrap();
+        assert!(seconds("Open").unwrap() >= 0.01);
+        assert_eq!(seconds("Closed"), Some(closed));
+        assert_eq!(seconds("HalfOpen"), None);
+
+        machine.consume(Result::Unsuccessful).unwrap();
+        let open = seconds("Open").unwrap();
+        assert!(seconds("HalfOpen").is_some());
+
+        // The time is accumulated over the visits of a state
+        sleep(Duration::from_millis(10));
+        machine.consume(Result::TimerTriggered).unwrap();
+        assert!(seconds("Open").unwrap() >= open + 0.01);
+        assert!(matches!(machine.into_inner(), CircuitBreaker::HalfOpen));
+    });
+
+    assert_eq!(
+        metrics.get("rust_fsm_transitions_total{machine=backend,from=Closed,input=Unsuccessful}"),
+        Some(1.0)
+    );
+    assert_eq!(
+        metrics.get("rust_fsm_transitions_total{machine=backend,from=Open,input=TimerTriggered}"),
+        Some(2.0)
+    );
+    assert_eq!(
+        metrics.get("rust_fsm_transitions_total{machine=backend,from=HalfOpen,input=Unsuccessful}"),
+        Some(1.0)
+    );
+    assert_eq!(
+        metrics.get("rust_fsm_rejections_total{machine=backend,state=Open,input=Successful}"),
+        Some(2.0)
+    );
+}
@@ -0,0 +1,36 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug, PartialEq)]
+    CircuitBreaker(Closed) => #[derive(Debug)] pub Result => #[derive(Debug, PartialEq)] pub Action
+
+    Closed => Unsuccessful => Open [SetupTimer, NotifyOps],
+    Open => TimerTriggered => HalfOpen [Log(&'static str => "half-open")],
+    HalfOpen => {
+        Successful => Closed [],
+        Unsuccessful => Open [SetupTimer]
+    },
+    exit HalfOpen [Log("left half-open"), NotifyOps],
+}
+
+#[test]
+fn multiple_outputs() {
+    let mut machine = CircuitBreaker::default();
+
+    let outputs = machine.consume(Result::Unsuccessful).unwrap();
+    assert!(outputs
+        .into_iter()
+        .eq([Action::SetupTimer, Action::NotifyOps]));
+
+    let outputs = machine.consume(Result::TimerTriggered).unwrap();
+    assert!(outputs.into_iter().eq([Action::Log("half-open")]));
+
+    let outputs = machine.consume(Result::Unsuccessful).unwrap();
+    assert_eq!(outputs.len(), 3);
+    assert!(outputs.into_iter().eq([
+        Action::Log("left half-open"),
+        Action::NotifyOps,
+        Action::SetupTimer
+    ]));
+    assert_eq!(machine, CircuitBreaker::Open);
+}
@@ -0,0 +1,73 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug, Clone, PartialEq)]
+    pub CircuitBreaker(Closed) => #[derive(Debug, Clone)] pub Result => #[derive(Debug, PartialEq)] pub Action
+
+    Closed => Unsuccessful => Open [SetupTimer],
+    Open => TimerTriggered => HalfOpen,
+    HalfOpen => {
+        Successful => Closed,
+        Unsuccessful => Open [SetupTimer],
+    },
+}
+
+/// Logs the transitions by the names of the states and inputs.
+#[derive(Default)]
+struct Log(Vec<String>);
+
+impl TransitionObserver<CircuitBreaker> for Log {
+    fn on_transition(
+        &mut self,
+        from: &CircuitBreaker,
+        input: &Result,
+        to: &CircuitBreaker,
+        outputs: &Option<Action>,
+    ) {
+        self.0.push(format!(
+            "{} --{}--> {} {outputs:?}",
+            from.name(),
+            input.name(),
+            to.name()
+        ));
+    }
+
+    fn on_rejected(&mut self, state: &CircuitBreaker, input: &Result) {
+        self.0
+            .push(format!("{} rejected {}", state.name(), input.name()));
+    }
+}
+
+#[test]
+fn observer() {
+    let mut machine = Observed::new(CircuitBreaker::Closed, Log::default());
+    machine.consume(Result::Unsuccessful).unwrap();
+    assert!(machine.consume(Result::Successful).is_err());
+    machine.consume(Result::TimerTriggered).unwrap();
+    assert_eq!(machine.state(), &CircuitBreaker::HalfOpen);
+
+    let (_, log) = machine.into_inner();
+    assert_eq!(
+        log.0,
+        [
+            "Closed --Unsuccessful--> Open Some(SetupTimer)",
+            "Open rejected Successful",
+            "Open --TimerTriggered--> HalfOpen None",
+        ]
+    );
+}
+
+#[test]
+fn closures() {
+    let mut count = 0;
+    let counter = |_: &CircuitBreaker, _: &Result, _: &CircuitBreaker, _: &Option<Action>| {
+        count += 1;
+    };
+    let mut machine = Observed::new(CircuitBreaker::Closed, (Log::default(), counter));
+    machine.consume(Result::Unsuccessful).unwrap();
+    machine.consume(Result::TimerTriggered).unwrap();
+    assert!(machine.consume(Result::TimerTriggered).is_err());
+    assert_eq!(machine.observer().0 .0.len(), 3);
+    drop(machine);
+    assert_eq!(count, 2);
+}
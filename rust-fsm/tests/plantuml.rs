@@ -0,0 +1,45 @@
+#![cfg(feature = "plantuml")]
+
+use rust_fsm::*;
+
+state_machine! {
+    pub Connection(Disconnected) => pub Event => pub Effect
+
+    Disconnected => Connect => Connected,
+    Connected => Disconnect => Disconnected [Goodbye],
+    Idle => Request(u32 => id if id > 0) => Busy,
+    Busy => Done => Idle,
+    Busy => Poll => _,
+    _ except Connected => Fail => Broken,
+    enter Busy [Lock],
+    exit Busy [Unlock],
+    state Connected { Idle, Busy },
+    final Broken,
+}
+
+#[test]
+fn plantuml() {
+    assert_eq!(
+        Connection::PLANTUML,
+        r#"@startuml
+hide empty description
+[*] --> Disconnected
+state Broken
+state Disconnected
+state Connected {
+    [*] --> Idle
+    state Idle
+    state Busy
+    Busy : entry / Lock
+    Busy : exit / Unlock
+}
+Disconnected --> Connected : Connect
+Connected --> Disconnected : Disconnect / Goodbye
+Idle --> Busy : Request(id) [id > 0]
+Busy --> Idle : Done
+Busy --> Busy : Poll
+Disconnected --> Broken : Fail
+Broken --> [*]
+@enduml"#
+    );
+}
@@ -0,0 +1,79 @@
+use rust_fsm::*;
+
+/// Deliberately not `Clone`: the queries evaluate the guards on references.
+#[derive(Debug, PartialEq)]
+pub struct Attempts(u8);
+
+#[derive(Debug)]
+pub struct Token(u32);
+
+state_machine! {
+    #[fsm(guards_by_ref)]
+    #[derive(Debug, PartialEq)]
+    pub Session(LoggedOut) => #[derive(Debug)] pub Action => pub Effect
+
+    LoggedOut => Login(String => name if !name.is_empty()) => LoggedIn(Attempts => Attempts(0)),
+    LoggedIn(Attempts => attempts if attempts.0 < 2) => Fail => LoggedIn(Attempts => Attempts(attempts.0 + 1)),
+    state Online { LoggedIn },
+    Online => {
+        Logout => LoggedOut,
+        Unlock(Token => t if t.0 == 42) => LoggedOut,
+    },
+}
+
+#[test]
+fn can_consume() {
+    let mut session = Session::default();
+    assert!(!session.can_consume(&Action::Login(String::new())));
+    assert!(session.can_consume(&Action::Login("user".to_string())));
+    assert!(!session.can_consume(&Action::Logout));
+
+    session.consume(Action::Login("user".to_string())).unwrap();
+    assert!(session.can_consume(&Action::Logout));
+    assert!(session.can_consume(&Action::Fail));
+    assert!(session.can_consume(&Action::Unlock(Token(42))));
+    assert!(!session.can_consume(&Action::Unlock(Token(7))));
+    session.consume(Action::Fail).unwrap();
+    session.consume(Action::Fail).unwrap();
+    assert_eq!(session, Session::LoggedIn(Attempts(2)));
+    assert!(!session.can_consume(&Action::Fail));
+    assert!(session.consume(Action::Fail).is_err());
+}
+
+#[test]
+fn accepted_input_names() {
+    let session = Session::default();
+    assert_eq!(session.accepted_input_names(), ["Login"]);
+    assert_eq!(
+        Session::LoggedIn(Attempts(0)).accepted_input_names(),
+        ["Fail", "Logout", "Unlock"]
+    );
+}
+
+pub mod unevaluated_guards {
+    use rust_fsm::*;
+
+    /// Neither `Clone` nor comparable by reference with a literal.
+    #[derive(Debug)]
+    pub struct Level(pub u8);
+
+    state_machine! {
+        pub Valve(Closed) => pub Command => pub Effect
+
+        Closed => Open(Level => level if level.0 > 3) => Opened(u8 => 0),
+        Opened(u8 => n if n < 2) => Open(_) => _,
+        Opened(_) => Close => Closed,
+    }
+}
+
+#[test]
+fn can_consume_without_evaluating_guards() {
+    use unevaluated_guards::*;
+
+    let valve = Valve::default();
+    assert!(valve.can_consume(&Command::Open(Level(0))));
+    assert!(!valve.can_consume(&Command::Close));
+    let valve = Valve::Opened(5);
+    assert!(valve.can_consume(&Command::Open(Level(0))));
+    assert!(valve.can_consume(&Command::Close));
+}
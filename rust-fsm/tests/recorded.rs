@@ -0,0 +1,100 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug, PartialEq)]
+    pub CircuitBreaker(Closed) => #[derive(Debug)] pub Result => #[derive(Debug, PartialEq)] pub Action
+
+    Closed => Unsuccessful => Open [SetupTimer, NotifyOps],
+    Open => TimerTriggered => HalfOpen,
+    HalfOpen => {
+        Successful => Closed,
+        Unsuccessful => Open [SetupTimer],
+    },
+}
+
+#[test]
+fn history() {
+    let mut machine = Recorded::<CircuitBreaker, 3>::new(CircuitBreaker::Closed);
+    assert_eq!(machine.history().count(), 0);
+
+    let outputs = machine.consume(Result::Unsuccessful).unwrap();
+    assert!(outputs
+        .into_iter()
+        .eq([Action::SetupTimer, Action::NotifyOps]));
+    // Impossible transitions are not recorded
+    assert!(machine.consume(Result::Successful).is_err());
+    machine.consume(Result::TimerTriggered).unwrap();
+
+    let history = machine
+        .history()
+        .map(|x| (x.from, x.input, x.to, x.outputs))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        history,
+        [
+            (
+                "Closed",
+                "Unsuccessful",
+                "Open",
+                &["SetupTimer", "NotifyOps"][..]
+            ),
+            ("Open", "TimerTriggered", "HalfOpen", &[]),
+        ]
+    );
+    assert!(machine
+        .history()
+        .zip(machine.history().skip(1))
+        .all(|(x, y)| x.timestamp <= y.timestamp));
+
+    // The oldest records are discarded
+    machine.consume(Result::Unsuccessful).unwrap();
+    machine.consume(Result::TimerTriggered).unwrap();
+    let history = machine
+        .history()
+        .map(|x| (x.from, x.to, x.outputs))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        history,
+        [
+            ("Open", "HalfOpen", &[][..]),
+            ("HalfOpen", "Open", &["SetupTimer"]),
+            ("Open", "HalfOpen", &[]),
+        ]
+    );
+
+    machine.clear_history();
+    assert_eq!(machine.history().count(), 0);
+    assert_eq!(machine.into_inner(), CircuitBreaker::HalfOpen);
+}
+
+/// Records the states only.
+struct States;
+
+impl Projection<CircuitBreaker> for States {
+    type State = &'static str;
+    type Input = ();
+    type Outputs = usize;
+
+    fn state(&self, state: &CircuitBreaker) -> &'static str {
+        state.name()
+    }
+
+    fn input(&self, _: &Result) {}
+
+    fn outputs(&self, outputs: &<CircuitBreaker as StateMachine>::Outputs<'_>) -> usize {
+        outputs.len()
+    }
+}
+
+#[test]
+fn projection() {
+    let mut machine = Recorded::<_, 4, _>::with_projection(CircuitBreaker::Closed, States);
+    machine.consume(Result::Unsuccessful).unwrap();
+    assert_eq!(machine.state(), &CircuitBreaker::Open);
+
+    let record = machine.history().next().unwrap();
+    assert_eq!(
+        (record.from, record.to, record.outputs),
+        ("Closed", "Open", 2)
+    );
+}
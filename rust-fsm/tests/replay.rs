@@ -0,0 +1,63 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[derive(Debug, PartialEq)]
+    pub CircuitBreaker(Closed) => #[derive(Debug, PartialEq)] pub Result => #[derive(Debug, PartialEq)] pub Action
+
+    Closed => Unsuccessful => Open [SetupTimer, NotifyOps],
+    Open => TimerTriggered => HalfOpen,
+    HalfOpen => {
+        Successful => Closed,
+        Unsuccessful => Open [SetupTimer],
+    },
+}
+
+#[test]
+fn strict() {
+    let log = [
+        Result::Unsuccessful,
+        Result::TimerTriggered,
+        Result::Unsuccessful,
+        Result::TimerTriggered,
+        Result::Successful,
+    ];
+    let replayed = replay(CircuitBreaker::initial(), log).unwrap();
+    assert_eq!(replayed.state, CircuitBreaker::Closed);
+    assert_eq!(
+        replayed.outputs,
+        [Action::SetupTimer, Action::NotifyOps, Action::SetupTimer]
+    );
+    assert!(replayed.rejected.is_empty());
+
+    let log = [
+        Result::Unsuccessful,
+        Result::Successful,
+        Result::TimerTriggered,
+    ];
+    let error = replay(CircuitBreaker::initial(), log).unwrap_err();
+    assert_eq!(error.index, 1);
+    assert_eq!(error.error.state, CircuitBreaker::Open);
+    assert_eq!(error.error.input, Result::Successful);
+    assert_eq!(
+        error.to_string(),
+        "input #1 of the log: cannot perform a state transition from the current state (Open) \
+         with the provided input (Successful)"
+    );
+}
+
+#[test]
+fn lenient() {
+    let log = vec![
+        Result::Successful,
+        Result::Unsuccessful,
+        Result::Successful,
+        Result::TimerTriggered,
+    ];
+    let replayed = replay_lenient(CircuitBreaker::initial(), log);
+    assert_eq!(replayed.state, CircuitBreaker::HalfOpen);
+    assert_eq!(replayed.outputs, [Action::SetupTimer, Action::NotifyOps]);
+    assert_eq!(
+        replayed.rejected,
+        [(0, Result::Successful), (2, Result::Successful)]
+    );
+}
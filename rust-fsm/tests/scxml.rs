@@ -0,0 +1,51 @@
+#![cfg(feature = "scxml")]
+
+use rust_fsm::*;
+
+state_machine! {
+    pub Connection(Disconnected) => pub Event => pub Effect
+
+    Disconnected => Connect => Connected,
+    Connected => Disconnect => Disconnected [Goodbye],
+    Idle => Request(u32 => id if id > 0) => Busy,
+    Busy => Done => Idle,
+    Busy => Poll => _,
+    _ except Connected => Fail => Broken,
+    enter Busy [Lock],
+    exit Busy [Unlock],
+    state Connected { Idle, Busy },
+    final Broken,
+}
+
+#[test]
+fn scxml() {
+    assert_eq!(
+        Connection::SCXML,
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0" name="Connection" initial="Disconnected">
+    <final id="Broken"/>
+    <state id="Disconnected">
+        <transition event="Connect" target="Connected"/>
+        <transition event="Fail" target="Broken"/>
+    </state>
+    <state id="Connected" initial="Idle">
+        <transition event="Disconnect" target="Disconnected">
+            <send event="Goodbye" target="#_parent"/>
+        </transition>
+        <state id="Idle">
+            <transition event="Request" cond="id &gt; 0" target="Busy"/>
+        </state>
+        <state id="Busy">
+            <onentry>
+                <send event="Lock" target="#_parent"/>
+            </onentry>
+            <onexit>
+                <send event="Unlock" target="#_parent"/>
+            </onexit>
+            <transition event="Done" target="Idle"/>
+            <transition event="Poll"/>
+        </state>
+    </state>
+</scxml>"##
+    );
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "serde")]
+
+use rust_fsm::*;
+use serde::{Deserialize, Serialize};
+
+/// An existing output type, serialized the same way as the generated ones.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum Effect {
+    Unlock,
+    Alarm(u8),
+}
+
+state_machine! {
+    #[fsm(serde)]
+    #[derive(Debug, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    Lock(Locked) => #[derive(Debug, PartialEq)] Code => ::crate::Effect
+
+    Locked => {
+        Enter(String => code if code == "1234") => Unlocked [Unlock],
+        Enter(_) => Failed(u8 => 1) [Alarm(u8 => 1)],
+    },
+    Failed(_) => Enter(code if code == "1234") => Unlocked [Unlock],
+    Failed(attempts) => Enter(_) => Failed(attempts + 1) [Alarm(attempts + 1)],
+    Unlocked => Close => Locked,
+}
+
+#[test]
+fn tags() {
+    for state in [Lock::Locked, Lock::Failed(2), Lock::Unlocked] {
+        let json = serde_json::to_value(&state).unwrap();
+        let tag = json
+            .as_str()
+            .or_else(|| json.as_object()?.keys().next().map(String::as_str))
+            .unwrap();
+        assert_eq!(tag, state.name());
+        assert_eq!(serde_json::from_value::<Lock>(json).unwrap(), state);
+    }
+    assert_eq!(
+        serde_json::to_string(&Lock::Failed(3)).unwrap(),
+        r#"{"Failed":3}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&Code::Enter("0000".into())).unwrap(),
+        r#"{"Enter":"0000"}"#
+    );
+    assert_eq!(serde_json::to_string(&Code::Close).unwrap(), r#""Close""#);
+    assert_eq!(
+        serde_json::to_string(&Effect::Alarm(1)).unwrap(),
+        r#"{"Alarm":1}"#
+    );
+}
+
+#[test]
+fn persist_and_replay() {
+    let mut machine = Lock::initial();
+    let inputs: Vec<Code> =
+        serde_json::from_str(r#"[{"Enter": "0000"}, {"Enter": "1111"}]"#).unwrap();
+    for input in inputs {
+        machine.consume(input).unwrap();
+    }
+    let stored = serde_json::to_string(&machine).unwrap();
+
+    let mut machine: Lock = serde_json::from_str(&stored).unwrap();
+    assert_eq!(machine, Lock::Failed(2));
+    assert_eq!(
+        machine.consume(Code::Enter("1234".into())).unwrap(),
+        Some(Effect::Unlock)
+    );
+}
+
+mod own_derive {
+    use rust_fsm::*;
+    use serde::{Deserialize, Serialize};
+
+    // Without `#[fsm(serde)]` the generated types can derive the traits
+    // themselves.
+    state_machine! {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        pub Door(Closed) => #[derive(Debug)] pub Action => pub Effect
+
+        Closed => Open => Opened,
+        Opened => Close => Closed,
+    }
+}
+
+#[test]
+fn own_derive() {
+    let mut door = own_derive::Door::default();
+    door.consume(own_derive::Action::Open).unwrap();
+    let json = serde_json::to_string(&door).unwrap();
+    assert_eq!(json, r#""opened""#);
+    let mut door: own_derive::Door = serde_json::from_str(&json).unwrap();
+    door.consume(own_derive::Action::Close).unwrap();
+    assert_eq!(door, own_derive::Door::Closed);
+}
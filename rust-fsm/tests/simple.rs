@@ -7,6 +7,7 @@ state_machine! {
     Closed => Key => Open,
     Open => Break => Broken,
     Closed => Break => Broken,
+    final Broken,
 }
 
 #[test]
@@ -16,6 +17,8 @@ fn simple() {
     println!("{machine:?}");
     machine.consume(Action::Key).unwrap();
     println!("{machine:?}");
+    assert!(!machine.is_final());
     machine.consume(Action::Break).unwrap();
     println!("{machine:?}");
+    assert!(machine.is_final());
 }
@@ -0,0 +1,96 @@
+#![cfg(feature = "serde")]
+
+use rust_fsm::*;
+
+mod v1 {
+    use rust_fsm::*;
+
+    state_machine! {
+        #[fsm(serde)]
+        #[derive(Debug, PartialEq)]
+        pub Door(Closed) => pub Action => pub Effect
+
+        Closed => {
+            Open => Opened,
+            Push(u8 => n) => Ajar(u8 => n),
+            Break => Broken,
+        },
+        Ajar(_) => Open => Opened,
+        Opened => Close => Closed,
+        final Broken,
+    }
+}
+
+mod v2 {
+    use rust_fsm::*;
+
+    state_machine! {
+        #[fsm(serde)]
+        #[derive(Debug, PartialEq)]
+        pub Door(Shut) => pub Action => pub Effect
+
+        Shut => {
+            Open => Open,
+            Push(u8 => n) => Ajar(u8 => n),
+        },
+        Ajar(_) => Open => Open,
+        Open => Close => Shut,
+    }
+}
+
+fn store<M: StateMachineMeta + serde::Serialize>(state: &M) -> String {
+    serde_json::to_string(&Snapshot::new(state)).unwrap()
+}
+
+fn migrate(state: &str) -> Option<&'static str> {
+    match state {
+        "Closed" => Some("Shut"),
+        "Opened" => Some("Open"),
+        _ => None,
+    }
+}
+
+#[test]
+fn same_definition() {
+    let stored = store(&v1::Door::Ajar(3));
+    assert_eq!(
+        stored,
+        format!(
+            r#"{{"definition_hash":{},"state":{{"Ajar":3}}}}"#,
+            v1::Door::DEFINITION_HASH
+        )
+    );
+    let mut deserializer = serde_json::Deserializer::from_str(&stored);
+    let door = Snapshot::<v1::Door>::restore(&mut deserializer, |_| unreachable!()).unwrap();
+    assert_eq!(door, v1::Door::Ajar(3));
+}
+
+#[test]
+fn migration() {
+    assert_ne!(v1::Door::DEFINITION_HASH, v2::Door::DEFINITION_HASH);
+
+    for (old, new) in [
+        (v1::Door::Closed, v2::Door::Shut),
+        (v1::Door::Opened, v2::Door::Open),
+        (v1::Door::Ajar(5), v2::Door::Ajar(5)),
+    ] {
+        let stored = store(&old);
+        let mut deserializer = serde_json::Deserializer::from_str(&stored);
+        let door = Snapshot::<v2::Door>::restore(&mut deserializer, migrate).unwrap();
+        assert_eq!(door, new);
+    }
+}
+
+#[test]
+fn unknown_state() {
+    let stored = store(&v1::Door::Broken);
+    let mut deserializer = serde_json::Deserializer::from_str(&stored);
+    let error = Snapshot::<v2::Door>::restore(&mut deserializer, migrate).unwrap_err();
+    assert!(matches!(&error, RestoreError::UnknownState(state) if state == "Broken"));
+    assert_eq!(error.to_string(), "unknown state `Broken`");
+
+    // Other errors are reported as they are
+    let mut deserializer = serde_json::Deserializer::from_str(r#"{"state":"Closed"}"#);
+    let error = Snapshot::<v2::Door>::restore(&mut deserializer, migrate).unwrap_err();
+    assert!(matches!(error, RestoreError::Deserialize(_)));
+}
@@ -0,0 +1,41 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[fsm(total)]
+    #[derive(Debug, PartialEq)]
+    pub Valve(Closed) => #[derive(Debug)] pub Command => #[derive(Debug, PartialEq)] pub Effect
+
+    Closed => {
+        Open => Opening(u8 => 0),
+        Close => _,
+    },
+    Opening(u8 => _) => Open => Opened,
+    Opened => Open => _,
+    state Moving { Opening, Opened },
+    Moving => Close => Closed [Stop],
+    _ => Fail => Jammed [Alarm],
+    final Jammed,
+}
+
+#[test]
+fn total_transitions() {
+    let mut machine = Valve::default();
+
+    assert_eq!(machine.consume_total(Command::Close), None);
+    assert_eq!(machine, Valve::Closed);
+    assert_eq!(machine.consume_total(Command::Open), None);
+    assert_eq!(machine, Valve::Opening(0));
+
+    let (machine, output) = machine.transition_total(Command::Close);
+    assert_eq!(machine, Valve::Closed);
+    assert_eq!(output, Some(Effect::Stop));
+
+    let (mut machine, output) = machine.transition_total(Command::Fail);
+    assert_eq!(output, Some(Effect::Alarm));
+
+    // Final states ignore any input
+    assert_eq!(machine.consume_total(Command::Open), None);
+    assert_eq!(machine, Valve::Jammed);
+    // The fallible interface still reports the transitions from final states
+    assert!(machine.consume(Command::Open).is_err());
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use rust_fsm::*;
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Metadata, Subscriber,
+};
+
+state_machine! {
+    #[derive(Debug)]
+    pub CircuitBreaker(Closed) => #[derive(Debug)] pub Result => #[derive(Debug)] pub Action
+
+    Closed => Unsuccessful => Open [SetupTimer],
+    Open => TimerTriggered => HalfOpen,
+    HalfOpen => {
+        Successful => Closed,
+        Unsuccessful => Open [SetupTimer],
+    },
+}
+
+/// Collects the events as `LEVEL field=value ...` lines.
+#[derive(Clone, Default)]
+struct Events(Arc<Mutex<Vec<String>>>);
+
+struct Line(String);
+
+impl Visit for Line {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push_str(&format!(" {field}={value:?}"));
+    }
+}
+
+impl Subscriber for Events {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+    fn event(&self, event: &Event<'_>) {
+        let mut line = Line(event.metadata().level().to_string());
+        event.record(&mut line);
+        self.0.lock().unwrap().push(line.0);
+    }
+    fn enter(&self, _: &span::Id) {}
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[test]
+fn events() {
+    let events = Events::default();
+    tracing::subscriber::with_default(events.clone(), || {
+        let mut machine = Traced::with_name(CircuitBreaker::Closed, "backend");
+        machine.consume(Result::Unsuccessful).unwrap();
+        assert!(machine.consume(Result::Successful).is_err());
+        machine.consume(Result::TimerTriggered).unwrap();
+    });
+    assert_eq!(
+        *events.0.lock().unwrap(),
+        [
+            format!(
+                "{} message=state transition machine=\"backend\" from=\"Closed\" \
+                 input=\"Unsuccessful\" to=\"Open\" outputs=[\"SetupTimer\"]",
+                Level::INFO
+            ),
+            format!(
+                "{} message=state transition impossible machine=\"backend\" \
+                 state=\"Open\" input=\"Successful\"",
+                Level::WARN
+            ),
+            format!(
+                "{} message=state transition machine=\"backend\" from=\"Open\" \
+                 input=\"TimerTriggered\" to=\"HalfOpen\" outputs=[]",
+                Level::INFO
+            ),
+        ]
+    );
+}
+
+#[test]
+fn type_name() {
+    let events = Events::default();
+    tracing::subscriber::with_default(events.clone(), || {
+        let mut machine = Traced::new(CircuitBreaker::Closed);
+        machine.consume(Result::Unsuccessful).unwrap();
+        assert!(matches!(machine.into_inner(), CircuitBreaker::Open));
+    });
+    let events = events.0.lock().unwrap();
+    assert!(events[0].contains("machine=\"traced::CircuitBreaker\""));
+}
@@ -0,0 +1,71 @@
+use rust_fsm::*;
+
+state_machine! {
+    #[fsm(strict)]
+    #[derive(Debug, PartialEq)]
+    pub Job(Idle) => #[derive(Debug)] pub Command => #[derive(Debug, PartialEq)] pub Effect
+
+    Idle => Start => Running,
+    Running => {
+        Pause => Paused,
+        Reset => _ [Ignored],
+    },
+    Paused => Resume => Running,
+    Failed(u8 => code if code < 3) => Retry => Running,
+    Running => Fail => Failed(u8 => 0),
+    _ except Done => Reset => Idle [Cleanup],
+    _ except Idle | Done => Finish => Done,
+    final Done,
+}
+
+#[test]
+fn wildcard_transitions() {
+    let mut machine = Job::default();
+
+    // `Idle` is excluded from finishing
+    assert!(machine.consume(Command::Finish).is_err());
+
+    // The transitions declared for a state take precedence
+    machine.consume(Command::Start).unwrap();
+    let output = machine.consume(Command::Reset).unwrap();
+    assert_eq!(output, Some(Effect::Ignored));
+    assert_eq!(machine, Job::Running);
+
+    machine.consume(Command::Pause).unwrap();
+    let output = machine.consume(Command::Reset).unwrap();
+    assert_eq!(output, Some(Effect::Cleanup));
+    assert_eq!(machine, Job::Idle);
+
+    let output = machine.consume(Command::Reset).unwrap();
+    assert_eq!(output, Some(Effect::Cleanup));
+    assert_eq!(machine, Job::Idle);
+
+    // States carrying data are matched as well
+    machine.consume(Command::Start).unwrap();
+    machine.consume(Command::Fail).unwrap();
+    assert_eq!(machine, Job::Failed(0));
+    machine.consume(Command::Finish).unwrap();
+    assert_eq!(machine, Job::Done);
+
+    // Final states are excluded
+    assert!(machine.consume(Command::Reset).is_err());
+}
+
+#[test]
+fn diagram() {
+    let edges = Job::MERMAID
+        .lines()
+        .filter(|line| line.contains("Reset"))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+    // `Running` handles `Reset` itself, so the `_` transition is not drawn for it
+    assert_eq!(
+        edges,
+        [
+            "Running --> Running: Reset / Ignored",
+            "Failed --> Idle: Reset / Cleanup",
+            "Idle --> Idle: Reset / Cleanup",
+            "Paused --> Idle: Reset / Cleanup",
+        ]
+    );
+}